@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use leptos::*;
-use leptos_animate::{AnimatedShow, FadeAnimation};
+use leptos_animate::{AnimatedShow, ExpandCollapseAnimation, FadeAnimation};
 
 #[component]
 pub fn AnimatedShowPage() -> impl IntoView {
@@ -12,6 +12,10 @@ pub fn AnimatedShowPage() -> impl IntoView {
     let enter_anim = FadeAnimation::new(Duration::from_millis(200), "ease-out");
     let leave_anim = FadeAnimation::new(Duration::from_millis(200), "ease-out");
 
+    let accordion_open = RwSignal::new(false);
+    let toggle_accordion = move |_| accordion_open.update(|v| *v = !*v);
+    let accordion_anim = ExpandCollapseAnimation::new(Duration::from_millis(250), "ease-in-out");
+
     view! {
         <div class="main-container animated-show-page">
             <div class="buttons">
@@ -24,6 +28,22 @@ pub fn AnimatedShowPage() -> impl IntoView {
                     "Visible Element"
                 </div>
             </AnimatedShow>
+            <div class="accordion">
+                <button on:click=toggle_accordion>
+                    "Toggle Section"
+                </button>
+                <AnimatedShow
+                    when=accordion_open.into_signal()
+                    enter_anim=accordion_anim.clone()
+                    leave_anim=accordion_anim
+                    keep_in_flow=true
+                >
+                    <div class="section">
+                        "The contents of this section expand and collapse, pushing the text below."
+                    </div>
+                </AnimatedShow>
+                <p>"Text below the section"</p>
+            </div>
         </div>
     }
 }
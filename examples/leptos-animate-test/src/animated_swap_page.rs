@@ -39,6 +39,9 @@ pub fn AnimatedSwapPage() -> impl IntoView {
     let set_variant_b = move |_| variant.set(Variant::VariantB);
     let set_variant_c = move |_| variant.set(Variant::VariantC);
 
+    let square = RwSignal::new(false);
+    let toggle_ratio = move |_| square.update(|square| *square = !*square);
+
     let resize_anim = SlidingAnimation::new(Duration::from_millis(200), "ease-out");
     let enter_anim = FadeAnimation::new(Duration::from_millis(200), "ease-out");
     let leave_anim = FadeAnimation::new(Duration::from_millis(200), "ease-out");
@@ -61,6 +64,18 @@ pub fn AnimatedSwapPage() -> impl IntoView {
                     <AnimatedSwap content enter_anim leave_anim />
                 </SizeTransition>
             </div>
+            <div class="buttons">
+                <button on:click=toggle_ratio>
+                    "Toggle aspect ratio"
+                </button>
+            </div>
+            <div class="content">
+                <SizeTransition resize_anim=SlidingAnimation::default()>
+                    <div class="media" class:square=square>
+                        {move || if square.get() { "1:1" } else { "16:9" }}
+                    </div>
+                </SizeTransition>
+            </div>
         </div>
     }
 }
@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use leptos::*;
+use leptos_animate::{AnimatedFor, FadeAnimation, SlidingAnimation};
+
+#[derive(Clone)]
+struct Row {
+    id: i32,
+    name: String,
+}
+
+#[component]
+pub fn AnimatedTablePage() -> impl IntoView {
+    let next_id = StoredValue::new(4);
+    let rows = RwSignal::new(vec![
+        Row {
+            id: 1,
+            name: "Apple".to_string(),
+        },
+        Row {
+            id: 2,
+            name: "Banana".to_string(),
+        },
+        Row {
+            id: 3,
+            name: "Cherry".to_string(),
+        },
+    ]);
+
+    let add_row = move |_| {
+        let id = next_id.get_value();
+        next_id.set_value(id + 1);
+        rows.update(|rows| {
+            rows.insert(
+                0,
+                Row {
+                    id,
+                    name: format!("Item {id}"),
+                },
+            )
+        });
+    };
+
+    let reverse = move |_| rows.update(|rows| rows.reverse());
+
+    let each = move || rows.get();
+
+    let key = |row: &Row| row.id;
+
+    let children = move |row: &Row| {
+        let id = row.id;
+
+        let remove_click = move |_| {
+            rows.update(|rows| rows.retain(|row| row.id != id));
+        };
+
+        view! {
+            <tr>
+                <td>{id}</td>
+                <td>{row.name.clone()}</td>
+                <td><button on:click=remove_click>"Remove"</button></td>
+            </tr>
+        }
+    };
+
+    let enter_anim = FadeAnimation::new(Duration::from_millis(300), "ease-out");
+    let leave_anim = FadeAnimation::new(Duration::from_millis(300), "ease-out");
+    let move_anim = SlidingAnimation::new(Duration::from_millis(300), "ease-out");
+
+    view! {
+        <div class="main-container animated-table-page">
+            <div class="buttons">
+                <button on:click=add_row>"+ Add"</button>
+                <button on:click=reverse>"Reverse"</button>
+            </div>
+            <table>
+                <thead>
+                    <tr>
+                        <th>"Id"</th>
+                        <th>"Name"</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    <AnimatedFor each key children table_rows=true enter_anim leave_anim move_anim />
+                </tbody>
+            </table>
+        </div>
+    }
+}
@@ -1,9 +1,15 @@
 use crate::animated_show_page::AnimatedShowPage;
 use crate::animated_swap_page::AnimatedSwapPage;
+use crate::animated_table_page::AnimatedTablePage;
+use crate::benchmark_page::BenchmarkPage;
+use crate::dense_grid_page::DenseGridPage;
 use crate::dynamics_page::DynamicsPage;
+use crate::fan_page::FanPage;
+use crate::sequence_page::SequencePage;
+use crate::svg_page::SvgPage;
 use leptos::*;
 use leptos_animate::{
-    AnimatedFor, AnimatedLayout, DynamicsAnimation, FadeAnimation, LayoutEntry, LayoutResult,
+    AnimatedFor, AnimatedLayout, Delay, DynamicsAnimation, FadeAnimation, LayoutEntry, LayoutResult,
 };
 use leptos_meta::*;
 use leptos_router::*;
@@ -28,6 +34,12 @@ pub fn App() -> impl IntoView {
                     <Route path="/dynamics" view=DynamicsPage/>
                     <Route path="/swap" view=AnimatedSwapPage/>
                     <Route path="/show" view=AnimatedShowPage/>
+                    <Route path="/table" view=AnimatedTablePage/>
+                    <Route path="/dense-grid" view=DenseGridPage/>
+                    <Route path="/svg" view=SvgPage/>
+                    <Route path="/fan" view=FanPage/>
+                    <Route path="/sequence" view=SequencePage/>
+                    <Route path="/benchmark" view=BenchmarkPage/>
                     <Route path="/*any" view=NotFound/>
                 </Routes>
             </main>
@@ -43,7 +55,13 @@ fn Navigation() -> impl IntoView {
             <A href="/layout">AnimatedLayout</A>
             <A href="/swap">AnimatedSwap</A>
             <A href="/show">AnimatedShow</A>
+            <A href="/table">Table</A>
+            <A href="/dense-grid">Dense Grid</A>
+            <A href="/svg">SVG</A>
+            <A href="/fan">Fan</A>
+            <A href="/sequence">Sequence</A>
             <A href="/dynamics">Dynamics</A>
+            <A href="/benchmark">Benchmark</A>
         </nav>
     }
 }
@@ -175,35 +193,24 @@ fn AnimatedLayoutPage() -> impl IntoView {
         match variant {
             WindowKind::Main => LayoutResult {
                 class: Some("main-mode".into()),
-                entries: vec![LayoutEntry {
-                    key: WindowKind::Main,
-                    view_fn: Box::new(main_view),
-                }],
+                entries: vec![LayoutEntry::new(WindowKind::Main, main_view)],
             },
             WindowKind::Edit => LayoutResult {
                 class: Some("edit-mode".into()),
                 entries: vec![
-                    LayoutEntry {
-                        key: WindowKind::Edit,
-                        view_fn: Box::new(edit_view),
-                    },
-                    LayoutEntry {
-                        key: WindowKind::Main,
-                        view_fn: Box::new(main_view),
-                    },
+                    LayoutEntry::new(WindowKind::Edit, edit_view),
+                    LayoutEntry::new(WindowKind::Main, main_view),
                 ],
             },
             WindowKind::EditOptions => LayoutResult {
                 class: Some("edit-options-mode".into()),
                 entries: vec![
-                    LayoutEntry {
-                        key: WindowKind::EditOptions,
-                        view_fn: Box::new(options_view),
-                    },
-                    LayoutEntry {
-                        key: WindowKind::Edit,
-                        view_fn: Box::new(edit_view),
-                    },
+                    // Enters after the edit view has made room for it.
+                    LayoutEntry::new(WindowKind::EditOptions, options_view).enter_anim(Delay::new(
+                        FadeAnimation::default(),
+                        Duration::from_millis(200),
+                    )),
+                    LayoutEntry::new(WindowKind::Edit, edit_view),
                 ],
             },
         }
@@ -0,0 +1,99 @@
+use leptos::*;
+use leptos_animate::AnimatedFor;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::js_sys::Promise;
+
+const ROWS: u32 = 1000;
+const UPDATES: u32 = 100;
+
+/// Replaces single rows of a 1000-row list, one after another, and measures how long each update
+/// takes until the DOM is up to date, including the microtask in which `AnimatedFor` measures the
+/// elements and starts their animations. Compare the numbers against a plain `For` to see the
+/// overhead of `AnimatedFor` itself.
+#[component]
+pub fn BenchmarkPage() -> impl IntoView {
+    let animated = RwSignal::new(true);
+    let running = RwSignal::new(false);
+    let result = RwSignal::new(None::<String>);
+    let next_key = StoredValue::new(ROWS);
+    let rows = RwSignal::new((0..ROWS).collect::<Vec<_>>());
+
+    let run = move |_| {
+        running.set(true);
+        result.set(None);
+
+        spawn_local(async move {
+            let performance = window().performance().unwrap();
+            let mut total = 0.0;
+            let mut slowest = 0.0_f64;
+
+            for i in 0..UPDATES {
+                let key = next_key.get_value();
+                next_key.set_value(key + 1);
+
+                let start = performance.now();
+                rows.update(|rows| {
+                    let index = (i * 7919) as usize % rows.len();
+                    rows[index] = key;
+                });
+                // Runs after the microtask that was queued by the update.
+                JsFuture::from(Promise::resolve(&JsValue::UNDEFINED))
+                    .await
+                    .unwrap();
+                let elapsed = performance.now() - start;
+
+                total += elapsed;
+                slowest = slowest.max(elapsed);
+
+                // Let the browser render the update before the next one.
+                next_frame().await;
+            }
+
+            result.set(Some(format!(
+                "{UPDATES} updates: {:.2}ms on average, {slowest:.2}ms at most",
+                total / UPDATES as f64
+            )));
+            running.set(false);
+        });
+    };
+
+    let toggle = move |_| animated.update(|animated| *animated = !*animated);
+
+    let each = move || rows.get();
+
+    let key = |v: &u32| *v;
+
+    let children = |v: &u32| {
+        view! { <div class="row">{*v}</div> }
+    };
+
+    view! {
+        <div class="main-container benchmark-page">
+            <div class="buttons">
+                <button on:click=run disabled=move || running.get()>"Run"</button>
+                <button on:click=toggle disabled=move || running.get()>
+                    {move || if animated.get() { "Use For" } else { "Use AnimatedFor" }}
+                </button>
+                <span>{move || result.get()}</span>
+            </div>
+            <div class="rows">
+                {move || {
+                    if animated.get() {
+                        view! { <AnimatedFor each key children /> }.into_view()
+                    } else {
+                        view! { <For each key children /> }.into_view()
+                    }
+                }}
+            </div>
+        </div>
+    }
+}
+
+/// Resolves in the next animation frame.
+async fn next_frame() {
+    let promise = Promise::new(&mut |resolve, _| {
+        window().request_animation_frame(&resolve).unwrap();
+    });
+    JsFuture::from(promise).await.unwrap();
+}
@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use leptos::*;
+use leptos_animate::{AnimatedFor, DynamicsAnimation, FadeAnimation};
+
+/// Masonry-like grid using `grid-auto-flow: dense` with differently sized cells. Adding or
+/// removing a single cell can reshuffle many of the others.
+#[component]
+pub fn DenseGridPage() -> impl IntoView {
+    let next_key = StoredValue::new(13);
+    let elements = RwSignal::new((1..13).collect::<Vec<i32>>());
+
+    let add_first = move |_| {
+        let key = next_key.get_value();
+        next_key.set_value(key + 1);
+        elements.update(|v| v.insert(0, key));
+    };
+
+    let rotate = move |_| {
+        elements.update(|v| {
+            if !v.is_empty() {
+                v.rotate_left(1);
+            }
+        })
+    };
+
+    let each = move || elements.get();
+
+    let key = |v: &i32| *v;
+
+    let children = move |c: &i32| {
+        let c = *c;
+
+        // Give the cells varying sizes so that the dense packing has something to do.
+        let class = match c % 4 {
+            0 => "element wide",
+            1 => "element tall",
+            2 => "element big",
+            _ => "element",
+        };
+
+        let remove_click = move |_| {
+            elements.update(|v| v.retain(|&x| x != c));
+        };
+
+        view! {
+            <button class=class on:click=remove_click>{c}</button>
+        }
+    };
+
+    let enter_anim = FadeAnimation::new(Duration::from_millis(300), "ease-out");
+    let leave_anim = FadeAnimation::new(Duration::from_millis(300), "ease-out");
+    let move_anim = DynamicsAnimation::new(2.0, 0.8, 0.0);
+
+    view! {
+        <div class="main-container dense-grid-page">
+            <div class="buttons">
+                <button on:click=add_first>"Insert first"</button>
+                <button on:click=rotate>"Rotate"</button>
+            </div>
+            <div class="dense-grid">
+                <AnimatedFor each key children animate_size=true enter_anim leave_anim move_anim />
+            </div>
+        </div>
+    }
+}
@@ -1,5 +1,5 @@
 use leptos::*;
-use leptos_animate::dynamics::SecondOrderDynamics;
+use leptos_animate::DynamicsAnimation;
 use leptos_chartistry::{AspectRatio, AxisMarker, Chart, IntoInner, Series, TickLabels};
 
 #[component]
@@ -60,24 +60,16 @@ pub fn DynamicsPage() -> impl IntoView {
 }
 
 fn run_dynamics(f: f32, z: f32, r: f32) -> Vec<DataPoint> {
-    let mut dynamics = SecondOrderDynamics::new(f, z, r, 0.0);
-    let mut data = vec![];
+    let anim = DynamicsAnimation::new(f, z, r);
 
-    const ITERATION_RATE: f32 = 15.0;
-    const DURATION: f32 = 2.0;
-
-    loop {
-        dynamics.update(1.0, 1.0 / ITERATION_RATE);
-        data.push(DataPoint {
-            x: data.len() as f64 / ITERATION_RATE as f64,
-            y: dynamics.get().max(-2.0).min(2.0),
-        });
-        if data.len() as f32 > ITERATION_RATE * DURATION {
-            break;
-        }
-    }
-
-    data
+    anim.samples()
+        .iter()
+        .enumerate()
+        .map(|(i, y)| DataPoint {
+            x: (i + 1) as f64 / DynamicsAnimation::SAMPLE_RATE as f64,
+            y: y.max(-2.0).min(2.0),
+        })
+        .collect()
 }
 
 struct DataPoint {
@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use leptos::*;
+use leptos_animate::{
+    AnimatedFor, AnimatedForHandle, AnimationConfigMove, ElementSnapshot, MoveAnimation,
+    MoveWaypoint, Position, Timing,
+};
+
+/// Swings the cards around a pivot below them while they move, like fanning out a hand of cards.
+struct FanMoveAnimation {
+    /// The largest rotation in degrees, reached halfway through the move.
+    angle: f64,
+}
+
+impl MoveAnimation for FanMoveAnimation {
+    fn animate(&self, from: ElementSnapshot, to: ElementSnapshot) -> AnimationConfigMove {
+        const WAYPOINTS: usize = 8;
+
+        let (from, to) = (from.position(), to.position());
+        let direction = (to.x - from.x).signum();
+
+        let waypoints = (1..=WAYPOINTS)
+            .map(|i| {
+                let t = i as f64 / (WAYPOINTS + 1) as f64;
+                let rotation = self.angle * direction * (t * std::f64::consts::PI).sin();
+                MoveWaypoint::new(t, Position::lerp(from, to, t)).rotation(rotation)
+            })
+            .collect();
+
+        let timing = Timing::new(Duration::from_millis(500)).timing_fn("ease-in-out");
+
+        AnimationConfigMove::new(timing)
+            .waypoints(waypoints)
+            .transform_origin("50% 250%")
+    }
+}
+
+#[component]
+pub fn FanPage() -> impl IntoView {
+    let fanned = RwSignal::new(false);
+    let handle = AnimatedForHandle::new();
+
+    let toggle = move |_| {
+        fanned.update(|fanned| *fanned = !*fanned);
+        handle.relayout();
+    };
+
+    let each = move || 1..=5;
+
+    let key = |v: &i32| *v;
+
+    let children = |c: &i32| {
+        view! { <div class="card">{*c}</div> }
+    };
+
+    view! {
+        <div class="main-container fan-page">
+            <div class="buttons">
+                <button on:click=toggle>"Fan out"</button>
+            </div>
+            <div class="cards" class:fanned=fanned>
+                <AnimatedFor each key children handle move_anim=FanMoveAnimation { angle: 25.0 } />
+            </div>
+        </div>
+    }
+}
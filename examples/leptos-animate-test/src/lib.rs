@@ -1,7 +1,13 @@
 mod animated_show_page;
 mod animated_swap_page;
+mod animated_table_page;
+mod benchmark_page;
 pub mod app;
+mod dense_grid_page;
 mod dynamics_page;
+mod fan_page;
+mod sequence_page;
+mod svg_page;
 
 #[cfg(feature = "hydrate")]
 #[wasm_bindgen::prelude::wasm_bindgen]
@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use leptos::*;
+use leptos_animate::{wait_for, SizeTransition, SlidingAnimation};
+use wasm_bindgen::JsCast;
+
+/// Two `SizeTransition`s that resize one after another: the second one only changes its contents
+/// once the animation of the first one has finished, using `wait_for`.
+#[component]
+pub fn SequencePage() -> impl IntoView {
+    let first_expanded = RwSignal::new(false);
+    let second_expanded = RwSignal::new(false);
+    let first = NodeRef::<html::Div>::new();
+
+    let toggle = move |_| {
+        let expanded = !first_expanded.get_untracked();
+        first_expanded.set(expanded);
+
+        // The `ResizeObserver` of the `SizeTransition` starts the animation right before the next
+        // paint, so it can be found in the animation frame after that.
+        request_animation_frame(move || {
+            request_animation_frame(move || {
+                // The animation runs on the wrapper of the `SizeTransition`.
+                let Some(wrapper) = first.get_untracked().and_then(|el| el.parent_element()) else {
+                    return;
+                };
+                let animations = wrapper.get_animations();
+
+                spawn_local(async move {
+                    for anim in animations.iter() {
+                        wait_for(&anim.unchecked_into()).await;
+                    }
+                    second_expanded.set(expanded);
+                });
+            });
+        });
+    };
+
+    let text = |expanded: bool| {
+        if expanded {
+            "This text needs a lot more room than before"
+        } else {
+            "Short"
+        }
+    };
+
+    let resize_anim = || SlidingAnimation::new(Duration::from_millis(400), "ease-in-out");
+
+    view! {
+        <div class="main-container sequence-page">
+            <div class="buttons">
+                <button on:click=toggle>"Toggle"</button>
+            </div>
+            <div class="boxes">
+                <SizeTransition resize_anim=resize_anim()>
+                    <div class="box" node_ref=first>{move || text(first_expanded.get())}</div>
+                </SizeTransition>
+                <SizeTransition resize_anim=resize_anim()>
+                    <div class="box">{move || text(second_expanded.get())}</div>
+                </SizeTransition>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use leptos::*;
+use leptos_animate::{AnimatedFor, FadeAnimation, SlidingAnimation};
+
+/// Bars inside of an `<svg>`. SVG has no layout, so the bars are placed using their `x`
+/// attribute, which depends on their index in the list.
+#[component]
+pub fn SvgPage() -> impl IntoView {
+    let next_key = StoredValue::new(6);
+    let elements = RwSignal::new(vec![1, 2, 3, 4, 5]);
+
+    let add_first = move |_| {
+        let key = next_key.get_value();
+        next_key.set_value(key + 1);
+        elements.update(|v| v.insert(0, key));
+    };
+
+    let shuffle = move |_| {
+        elements.update(|v| {
+            if v.len() > 1 {
+                v.rotate_left(1);
+                v.swap(0, 1);
+            }
+        })
+    };
+
+    let each = move || elements.get();
+
+    let key = |v: &i32| *v;
+
+    let children = move |c: &i32| {
+        let c = *c;
+
+        let x = move || elements.with(|v| v.iter().position(|&x| x == c).unwrap_or_default() * 60);
+        let height = 20 + (c * 37) % 100;
+
+        let remove_click = move |_| {
+            elements.update(|v| v.retain(|&x| x != c));
+        };
+
+        view! {
+            <rect class="bar" x=x y=120 - height width=50 height=height on:click=remove_click />
+        }
+    };
+
+    let enter_anim = FadeAnimation::new(Duration::from_millis(300), "ease-out");
+    let leave_anim = FadeAnimation::new(Duration::from_millis(300), "ease-out");
+    let move_anim = SlidingAnimation::new(Duration::from_millis(300), "ease-out");
+
+    view! {
+        <div class="main-container svg-page">
+            <div class="buttons">
+                <button on:click=add_first>"Insert first"</button>
+                <button on:click=shuffle>"Shuffle"</button>
+            </div>
+            <svg width="600" height="120">
+                <AnimatedFor each key children keep_in_flow=true enter_anim leave_anim move_anim />
+            </svg>
+        </div>
+    }
+}
@@ -0,0 +1,576 @@
+use crate::Extent;
+use leptos::Oco;
+use std::time::Duration;
+use web_sys::js_sys;
+use web_sys::{Animation, AnimationTimeline, CompositeOperation, FillMode, PlaybackDirection};
+
+/// Options for [`animate`]. These map to the
+/// [`KeyframeAnimationOptions`](https://developer.mozilla.org/en-US/docs/Web/API/Element/animate#options)
+/// of the Web Animations API.
+#[derive(Clone, Debug)]
+pub struct AnimateOptions {
+    /// Duration of the animation.
+    pub duration: Duration,
+
+    /// Whether the animation's effects should be applied before it starts or after it finished.
+    /// The fill mode can shadow timing bugs, so the components in this crate avoid it.
+    pub fill: FillMode,
+
+    /// Timing function of the animation, for example `ease-out` or `linear(...)`.
+    pub easing: Option<Oco<'static, str>>,
+
+    /// Time to wait before the animation starts.
+    pub delay: Duration,
+
+    /// How often the animation repeats. Use `f64::INFINITY` to repeat forever.
+    pub iterations: f64,
+
+    /// Whether the animation runs forwards, backwards or alternates between iterations.
+    pub direction: PlaybackDirection,
+
+    /// How the animation's values are combined with other animations on the same property.
+    pub composite: CompositeOperation,
+
+    /// Pseudo-element to animate instead of the element itself, for example `::before`.
+    pub pseudo_element: Option<Oco<'static, str>>,
+
+    /// The timeline to run the animation on, for example a `ScrollTimeline` for scroll-driven
+    /// animations. Uses the document's timeline if unset.
+    pub timeline: Option<AnimationTimeline>,
+
+    /// Whether a filling animation keeps its end state when the browser removes it. Browsers
+    /// automatically remove finished filling animations once newer filling animations on the
+    /// same element replace all of their properties, and the element would snap back as soon
+    /// as those end or get cancelled. With this, the animation's values are written to the
+    /// inline style (using `commitStyles()`) when it gets removed, so the element holds its
+    /// state until it is taken out of the DOM. Only has an effect with `FillMode::Forwards`
+    /// or `FillMode::Both`.
+    pub keep_when_replaced: bool,
+}
+
+impl AnimateOptions {
+    /// Create options for a single, non-filling run of the given duration.
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            ..Default::default()
+        }
+    }
+
+    pub fn fill(mut self, fill: FillMode) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    pub fn easing<TF: Into<Oco<'static, str>>>(mut self, easing: TF) -> Self {
+        self.easing = Some(easing.into());
+        self
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn iterations(mut self, iterations: f64) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn direction(mut self, direction: PlaybackDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn composite(mut self, composite: CompositeOperation) -> Self {
+        self.composite = composite;
+        self
+    }
+
+    pub fn pseudo_element<PE: Into<Oco<'static, str>>>(mut self, pseudo_element: PE) -> Self {
+        self.pseudo_element = Some(pseudo_element.into());
+        self
+    }
+
+    pub fn timeline(mut self, timeline: AnimationTimeline) -> Self {
+        self.timeline = Some(timeline);
+        self
+    }
+
+    pub fn keep_when_replaced(mut self, keep_when_replaced: bool) -> Self {
+        self.keep_when_replaced = keep_when_replaced;
+        self
+    }
+}
+
+impl Default for AnimateOptions {
+    fn default() -> Self {
+        Self {
+            duration: Duration::ZERO,
+            fill: FillMode::None,
+            easing: None,
+            delay: Duration::ZERO,
+            iterations: 1.0,
+            direction: PlaybackDirection::Normal,
+            composite: CompositeOperation::Replace,
+            pseudo_element: None,
+            timeline: None,
+            keep_when_replaced: false,
+        }
+    }
+}
+
+/// Wrapper around the `animate` function in the Web Animations API because in web_sys it is still
+/// unstable and that causes some problems with cranelift.
+///
+/// This is the same function that all the components in this crate use to run their animations,
+/// so it can be used to drive custom animations in the same way.
+///
+/// # Example
+/// ```
+/// let keyframes: Array = [
+///     serde_wasm_bindgen::to_value(&MyKeyframe { opacity: 0.0 }).unwrap(),
+///     serde_wasm_bindgen::to_value(&MyKeyframe { opacity: 1.0 }).unwrap(),
+/// ]
+/// .into_iter()
+/// .collect();
+///
+/// let options = AnimateOptions::new(Duration::from_millis(300))
+///     .easing("ease-out")
+///     .delay(Duration::from_millis(100));
+///
+/// let anim = animate(&el, Some(&keyframes.into()), &options);
+/// ```
+pub fn animate(
+    el: &web_sys::HtmlElement,
+    keyframes: Option<&js_sys::Object>,
+    options: &AnimateOptions,
+) -> Animation {
+    #[cfg(not(feature = "ssr"))]
+    {
+        use web_sys::KeyframeAnimationOptions;
+        let mut js_options = KeyframeAnimationOptions::new();
+
+        js_options
+            .duration(&(options.duration.as_secs_f64() * 1000.0).into())
+            .fill(options.fill)
+            .delay(options.delay.as_secs_f64() * 1000.0)
+            .iterations(options.iterations)
+            .direction(options.direction)
+            .composite(options.composite);
+
+        if let Some(easing) = &options.easing {
+            #[cfg(debug_assertions)]
+            if let Err(err) = validate_easing(easing) {
+                leptos::logging::warn!("Invalid timing function {:?}: {}", easing.as_str(), err);
+            }
+
+            js_options.easing(easing.as_str());
+        }
+
+        // Not yet supported by web_sys.
+        if let Some(pseudo_element) = &options.pseudo_element {
+            js_sys::Reflect::set(
+                &js_options,
+                &"pseudoElement".into(),
+                &pseudo_element.as_str().into(),
+            )
+            .unwrap();
+        }
+
+        if let Some(timeline) = &options.timeline {
+            js_options.timeline(Some(timeline));
+        }
+
+        let anim = el.animate_with_keyframe_animation_options(keyframes, &js_options);
+
+        if options.keep_when_replaced && matches!(options.fill, FillMode::Forwards | FillMode::Both)
+        {
+            commit_styles_on_remove(&anim);
+        }
+
+        anim
+    }
+    #[cfg(feature = "ssr")]
+    {
+        _ = el;
+        _ = keyframes;
+        _ = options;
+        unimplemented!("Animation API can't be run on the server")
+    }
+}
+
+/// Write the current values of the animation to the element's inline style.
+#[cfg(not(feature = "ssr"))]
+fn commit_styles(anim: &Animation) {
+    use wasm_bindgen::JsCast;
+
+    // Not yet supported by web_sys.
+    let Ok(commit_styles) = js_sys::Reflect::get(anim, &"commitStyles".into()) else {
+        return;
+    };
+
+    // Throws if the element isn't rendered, in which case there's nothing to keep.
+    if let Ok(commit_styles) = commit_styles.dyn_into::<js_sys::Function>() {
+        _ = commit_styles.call0(anim);
+    }
+}
+
+/// Commit the styles of the animation once the browser automatically removes it, see
+/// [`AnimateOptions::keep_when_replaced`].
+#[cfg(not(feature = "ssr"))]
+fn commit_styles_on_remove(anim: &Animation) {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+
+    let closure = Closure::<dyn Fn(web_sys::Event)>::new({
+        let anim = anim.clone();
+        move |_| commit_styles(&anim)
+    })
+    .into_js_value();
+
+    // Not yet supported by web_sys (`onremove`).
+    anim.add_event_listener_with_callback("remove", closure.unchecked_ref())
+        .unwrap();
+}
+
+/// Convert a keyframe to a JavaScript object. Unlike `serde_wasm_bindgen::to_value`, this also
+/// turns maps (used by `#[serde(flatten)]`, see [`Keyframe`][crate::Keyframe]) into plain objects.
+pub(crate) fn keyframe_to_js<T: serde::Serialize>(keyframe: &T) -> wasm_bindgen::JsValue {
+    const SERIALIZER: serde_wasm_bindgen::Serializer =
+        serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+
+    keyframe.serialize(&SERIALIZER).unwrap()
+}
+
+/// The size of the element's border box as it is currently rendered, so including the effects of
+/// running animations.
+pub(crate) fn border_box_extent(el: &web_sys::HtmlElement) -> Extent {
+    let rect = el.get_bounding_client_rect();
+    Extent {
+        width: rect.width(),
+        height: rect.height(),
+    }
+}
+
+/// Whether the browser supports
+/// [scroll-driven animations](https://developer.mozilla.org/en-US/docs/Web/CSS/CSS_scroll-driven_animations)
+/// using a `ScrollTimeline`. Check this before creating one to pass as a `timeline`, and fall back
+/// to regular animations otherwise.
+pub fn supports_scroll_timelines() -> bool {
+    !leptos::leptos_dom::is_server()
+        && js_sys::Reflect::has(&leptos::window(), &"ScrollTimeline".into()).unwrap_or(false)
+}
+
+/// Returns a signal that tracks whether the user prefers reduced motion (the
+/// [`prefers-reduced-motion`](https://developer.mozilla.org/en-US/docs/Web/CSS/@media/prefers-reduced-motion)
+/// media query). It updates when the setting changes, and is always `false` on the server.
+///
+/// # Example
+/// ```
+/// let reduced_motion = use_prefers_reduced_motion();
+///
+/// let move_anim = move || -> AnyMoveAnimation {
+///     if reduced_motion.get() {
+///         SlidingAnimation::new(Duration::ZERO, "linear").into()
+///     } else {
+///         DynamicsAnimation::new(2.0, 0.65, 0.0).into()
+///     }
+/// };
+/// ```
+pub fn use_prefers_reduced_motion() -> leptos::Signal<bool> {
+    use leptos::SignalSet;
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+
+    let reduced = leptos::RwSignal::new(false);
+
+    if leptos::leptos_dom::is_server() {
+        return reduced.into();
+    }
+
+    let Some(query) = leptos::window()
+        .match_media("(prefers-reduced-motion: reduce)")
+        .ok()
+        .flatten()
+    else {
+        return reduced.into();
+    };
+
+    reduced.set(query.matches());
+
+    let closure = Closure::<dyn Fn(web_sys::Event)>::new({
+        let query = query.clone();
+        move |_| {
+            reduced.try_set(query.matches());
+        }
+    });
+
+    query
+        .add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())
+        .unwrap();
+
+    leptos::on_cleanup(move || {
+        _ = query.remove_event_listener_with_callback("change", closure.as_ref().unchecked_ref());
+    });
+
+    reduced.into()
+}
+
+/// Returns a future that resolves once the animation has finished, so that animations can be
+/// sequenced in async code. It also resolves if the animation gets cancelled.
+///
+/// # Example
+/// ```
+/// spawn_local(async move {
+///     let options = AnimateOptions::new(Duration::from_millis(300)).easing("ease-out");
+///
+///     wait_for(&animate(&first, Some(&expand_keyframes), &options)).await;
+///     wait_for(&animate(&second, Some(&expand_keyframes), &options)).await;
+/// });
+/// ```
+pub fn wait_for(animation: &Animation) -> impl std::future::Future<Output = ()> {
+    let finished = animation.finished();
+
+    async move {
+        if let Ok(finished) = finished {
+            // Cancelling the animation rejects the promise, which is fine for our purposes.
+            _ = wasm_bindgen_futures::JsFuture::from(finished).await;
+        }
+    }
+}
+
+/// Removes the inline `properties` from the element once all animations inside of it (including
+/// its own) have finished or got cancelled. `is_current` is checked right before, so that a
+/// transition that has started in the meantime can keep the styles.
+pub(crate) fn remove_styles_when_finished(
+    el: web_sys::HtmlElement,
+    properties: Vec<&'static str>,
+    is_current: impl FnOnce() -> bool + 'static,
+) {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::{JsCast, JsValue};
+
+    let mut options = web_sys::GetAnimationsOptions::new();
+    options.subtree(true);
+
+    let finished: js_sys::Array = el
+        .get_animations_with_options(&options)
+        .iter()
+        .filter_map(|anim| anim.unchecked_into::<Animation>().finished().ok())
+        .collect();
+
+    let closure = Closure::once(move |_: JsValue| {
+        if is_current() {
+            for property in properties {
+                el.style().remove_property(property).unwrap();
+            }
+        }
+    });
+
+    // Cancelled animations reject their `finished` promise, so wait for all of them to settle.
+    _ = js_sys::Promise::all_settled(&finished).then(&closure);
+    closure.forget();
+}
+
+/// Calls `restore` to undo the inline styles that were set on the element for the duration of
+/// `anim` (like `will-change`) once it has finished or got cancelled. Animations that are started
+/// on the element in the meantime keep the styles, so cancelling an animation and immediately
+/// starting the next one doesn't drop them. The `onfinish` and `oncancel` handlers stay free.
+pub(crate) fn restore_styles_on_end(
+    el: &web_sys::HtmlElement,
+    anim: &Animation,
+    restore: fn(&web_sys::HtmlElement),
+) {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+
+    let closure = Closure::<dyn Fn(web_sys::Event)>::new({
+        let el = el.clone();
+        move |_| {
+            let is_animating = el.get_animations().iter().any(|anim| {
+                let state = anim.unchecked_into::<Animation>().play_state();
+                state == web_sys::AnimationPlayState::Running
+                    || state == web_sys::AnimationPlayState::Paused
+            });
+
+            if !is_animating {
+                restore(&el);
+            }
+        }
+    })
+    .into_js_value();
+
+    for event in ["finish", "cancel"] {
+        anim.add_event_listener_with_callback(event, closure.unchecked_ref())
+            .unwrap();
+    }
+}
+
+/// Returns a signal with the progress of the animation, from 0 to 1 (before the timing function
+/// is applied, and per iteration). It gets updated in every animation frame while the animation
+/// is running, which stops once it has finished (setting it to 1) or got cancelled (keeping the
+/// last value).
+///
+/// # Example
+/// ```
+/// let progress = animation_progress(&anim);
+///
+/// view! {
+///     <progress value=progress />
+/// }
+/// ```
+pub fn animation_progress(animation: &Animation) -> leptos::Signal<f64> {
+    let progress = leptos::RwSignal::new(computed_progress(animation).unwrap_or_default());
+
+    if !leptos::leptos_dom::is_server() {
+        sample_progress(animation.clone(), progress);
+    }
+
+    progress.into()
+}
+
+/// Updates `progress` once per animation frame until the animation ends or the signal has been
+/// disposed.
+fn sample_progress(animation: Animation, progress: leptos::RwSignal<f64>) {
+    use leptos::SignalSet;
+    use web_sys::AnimationPlayState;
+
+    leptos::request_animation_frame(move || match animation.play_state() {
+        AnimationPlayState::Finished => {
+            progress.try_set(1.0);
+        }
+        AnimationPlayState::Idle => {}
+        _ => {
+            let still_used = computed_progress(&animation)
+                .map_or(true, |value| progress.try_set(value).is_none());
+
+            if still_used {
+                sample_progress(animation, progress);
+            }
+        }
+    });
+}
+
+/// The current `progress` of the animation's computed timing. It's `None` while the animation
+/// isn't in its active phase, for example during its delay.
+fn computed_progress(animation: &Animation) -> Option<f64> {
+    let timing = animation.effect()?.get_computed_timing();
+
+    // `ComputedEffectTiming` has no getters in web_sys.
+    js_sys::Reflect::get(&timing, &"progress".into())
+        .ok()?
+        .as_f64()
+}
+
+/// Basic syntax check for CSS timing functions. The browser silently falls back to a linear
+/// timing (or doesn't run the animation at all) on invalid strings, so [`animate`] uses this in
+/// debug builds to warn about obvious mistakes. It doesn't catch everything the browser would
+/// reject.
+#[cfg(all(debug_assertions, not(feature = "ssr")))]
+fn validate_easing(easing: &str) -> Result<(), String> {
+    const KEYWORDS: [&str; 7] = [
+        "linear",
+        "ease",
+        "ease-in",
+        "ease-out",
+        "ease-in-out",
+        "step-start",
+        "step-end",
+    ];
+
+    let easing = easing.trim();
+
+    if KEYWORDS.contains(&easing) {
+        return Ok(());
+    }
+
+    let Some((name, args)) = easing
+        .strip_suffix(')')
+        .and_then(|easing| easing.split_once('('))
+    else {
+        return Err(format!(
+            "expected one of {KEYWORDS:?}, cubic-bezier(), steps() or linear()"
+        ));
+    };
+
+    let args = args.split(',').map(str::trim).collect::<Vec<_>>();
+
+    let parse_number = |v: &str| {
+        v.parse::<f64>()
+            .map_err(|_| format!("{v:?} is not a number"))
+    };
+
+    match name.trim() {
+        "cubic-bezier" => {
+            let [x1, y1, x2, y2] = args[..] else {
+                return Err(format!(
+                    "cubic-bezier() takes 4 numbers, got {}",
+                    args.len()
+                ));
+            };
+            let (x1, x2) = (parse_number(x1)?, parse_number(x2)?);
+            parse_number(y1)?;
+            parse_number(y2)?;
+
+            if !(0.0..=1.0).contains(&x1) || !(0.0..=1.0).contains(&x2) {
+                return Err("the x values of cubic-bezier() must be between 0 and 1".to_string());
+            }
+        }
+        "steps" => {
+            let (count, position) = match args[..] {
+                [count] => (count, None),
+                [count, position] => (count, Some(position)),
+                _ => {
+                    return Err(format!(
+                        "steps() takes 1 or 2 arguments, got {}",
+                        args.len()
+                    ))
+                }
+            };
+
+            if !count.parse::<u32>().is_ok_and(|count| count > 0) {
+                return Err(format!("{count:?} is not a positive step count"));
+            }
+
+            const POSITIONS: [&str; 6] = [
+                "jump-start",
+                "jump-end",
+                "jump-none",
+                "jump-both",
+                "start",
+                "end",
+            ];
+
+            if let Some(position) = position {
+                if !POSITIONS.contains(&position) {
+                    return Err(format!("expected a step position of {POSITIONS:?}"));
+                }
+            }
+        }
+        "linear" => {
+            if args.len() < 2 {
+                return Err("linear() needs at least 2 stops".to_string());
+            }
+
+            for stop in args {
+                let mut parts = stop.split_whitespace();
+
+                parse_number(parts.next().unwrap_or_default())?;
+
+                let percentages = parts.collect::<Vec<_>>();
+                if percentages.len() > 2
+                    || percentages.iter().any(|p| {
+                        p.strip_suffix('%')
+                            .map_or(true, |p| p.parse::<f64>().is_err())
+                    })
+                {
+                    return Err(format!("{stop:?} is not a valid linear() stop"));
+                }
+            }
+        }
+        name => return Err(format!("unknown timing function {name:?}")),
+    }
+
+    Ok(())
+}
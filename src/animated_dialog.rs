@@ -0,0 +1,225 @@
+use leptos::*;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::js_sys::{self, Array};
+use web_sys::Animation;
+
+use crate::animate::keyframe_to_js;
+use crate::animated_for::anim_duration;
+use crate::{animate, AnimateOptions, AnyEnterAnimation, AnyLeaveAnimation, FadeAnimation};
+
+/// The custom property that the backdrop animation of [`AnimatedDialog`] animates.
+const BACKDROP_OPACITY_PROPERTY: &str = "--backdrop-opacity";
+
+/// A native `<dialog>` element that plays an enter-animation when it opens and a leave-animation
+/// before it closes. The dialog only gets closed once the leave-animation has finished, so it
+/// stays in the top layer while it animates out.
+///
+/// # Closing
+/// When the user presses Escape (or the dialog gets closed some other way, for example by a
+/// `<form method="dialog">`), `on_close_request` is called. Set `open` to `false` in there to
+/// animate the dialog out. A dialog that has already been closed by the browser can't be animated
+/// anymore, so only the Escape key, which can be intercepted, gets a leave-animation. Without
+/// `on_close_request`, the browser closes the dialog right away, and `open` gets out of sync.
+///
+/// # Backdrop
+/// The `::backdrop` can't be animated directly. Instead, the `--backdrop-opacity` custom property
+/// on the dialog fades from 0 to 1 (and back) over the duration of the dialog's animation. Use it
+/// in the CSS of the backdrop, which inherits it from the dialog:
+///
+/// ```css
+/// .my-dialog::backdrop {
+///     background: rgb(0 0 0 / 40%);
+///     opacity: var(--backdrop-opacity, 1);
+/// }
+/// ```
+///
+/// # Example
+/// ```
+/// let open = RwSignal::new(false);
+///
+/// view! {
+///     <button on:click=move |_| open.set(true)>"Delete"</button>
+///     <AnimatedDialog open class="my-dialog" on_close_request=Callback::new(move |_| open.set(false))>
+///         <p>"Really delete this file?"</p>
+///         <button on:click=move |_| open.set(false)>"Cancel"</button>
+///     </AnimatedDialog>
+/// }
+/// ```
+#[component]
+pub fn AnimatedDialog(
+    /// Whether the dialog is open.
+    #[prop(into)]
+    open: Signal<bool>,
+
+    /// The contents of the dialog.
+    children: Children,
+
+    /// Whether to open the dialog as a modal, using `showModal()`. Modal dialogs have a backdrop
+    /// and make the rest of the page inert. Otherwise the dialog is opened using `show()`.
+    #[prop(default = true)]
+    modal: bool,
+
+    /// The enter animation of the dialog.
+    #[prop(default = FadeAnimation::default().into(), into)]
+    enter_anim: AnyEnterAnimation,
+
+    /// The leave animation of the dialog.
+    #[prop(default = FadeAnimation::default().into(), into)]
+    leave_anim: AnyLeaveAnimation,
+
+    /// Whether to play the enter-animation if the dialog is already open on the first render.
+    #[prop(default = false)]
+    appear: bool,
+
+    /// Called when the user asks to close the dialog, see the section about closing above.
+    #[prop(optional)]
+    on_close_request: Option<Callback<()>>,
+
+    /// Called when the leave-animation has finished and the dialog has been closed.
+    #[prop(optional)]
+    on_closed: Option<Callback<()>>,
+
+    /// Class of the `<dialog>`.
+    #[prop(optional, into)]
+    class: Option<Oco<'static, str>>,
+) -> impl IntoView {
+    let dialog = NodeRef::<html::Dialog>::new();
+    let cur_anims = StoredValue::new(Vec::<Animation>::new());
+
+    create_effect(move |prev: Option<bool>| {
+        let is_open = open.get();
+
+        if prev == Some(is_open) {
+            return is_open;
+        }
+        let Some(dialog) = dialog.get_untracked() else {
+            return is_open;
+        };
+        let el: web_sys::HtmlDialogElement = (*dialog).clone();
+
+        // Interrupt a running enter or leave animation. Cancelled animations don't call their
+        // `onfinish` handler, so an interrupted leave animation won't close the dialog.
+        cur_anims.update_value(|anims| {
+            for anim in anims.drain(..) {
+                anim.cancel();
+            }
+        });
+
+        if is_open {
+            if !el.open() {
+                if modal {
+                    el.show_modal().unwrap();
+                } else {
+                    el.show();
+                }
+            }
+
+            if prev.is_some() || appear {
+                let anim = enter_anim.animate(&el);
+                let mut anims = vec![anim.clone()];
+                if modal {
+                    anims.push(fade_backdrop(&el, &anim, true));
+                }
+                cur_anims.set_value(anims);
+            }
+        } else if el.open() {
+            let (anim, _) = leave_anim.animate(&el);
+
+            let closure = Closure::<dyn Fn(web_sys::Event)>::new({
+                let el = el.clone();
+                move |_| {
+                    el.close();
+                    if let Some(on_closed) = on_closed {
+                        on_closed(());
+                    }
+                }
+            })
+            .into_js_value();
+            anim.set_onfinish(Some(closure.unchecked_ref()));
+
+            let mut anims = vec![anim.clone()];
+            if modal {
+                anims.push(fade_backdrop(&el, &anim, false));
+            }
+            cur_anims.set_value(anims);
+        }
+
+        is_open
+    });
+
+    on_cleanup(move || {
+        cur_anims.try_with_value(|anims| {
+            for anim in anims {
+                anim.cancel();
+            }
+        });
+    });
+
+    // Pressing Escape fires `cancel` before the dialog gets closed, which can be prevented.
+    let on_cancel = move |ev: web_sys::Event| {
+        if let Some(on_close_request) = on_close_request {
+            ev.prevent_default();
+            on_close_request(());
+        }
+    };
+
+    // The dialog got closed without us, so `open` should follow.
+    let on_close = move |_| {
+        if open.get_untracked() {
+            if let Some(on_close_request) = on_close_request {
+                on_close_request(());
+            }
+        }
+    };
+
+    view! {
+        <dialog node_ref=dialog class=class on:cancel=on_cancel on:close=on_close>
+            {children()}
+        </dialog>
+    }
+}
+
+#[derive(serde::Serialize)]
+struct BackdropKeyframe {
+    #[serde(rename = "--backdrop-opacity")]
+    opacity: f64,
+}
+
+/// Fade the `--backdrop-opacity` of the dialog in or out over the duration of `anim`.
+fn fade_backdrop(el: &web_sys::HtmlElement, anim: &Animation, fade_in: bool) -> Animation {
+    register_backdrop_property();
+
+    let (from, to) = if fade_in { (0.0, 1.0) } else { (1.0, 0.0) };
+    let keyframes: Array = [from, to]
+        .into_iter()
+        .map(|opacity| keyframe_to_js(&BackdropKeyframe { opacity }))
+        .collect();
+
+    let options = AnimateOptions::new(anim_duration(anim).unwrap_or_default());
+    animate(el, Some(&keyframes.into()), &options)
+}
+
+/// Register `--backdrop-opacity` as a number, so that it gets interpolated instead of flipping
+/// from one value to the other halfway through the animation.
+fn register_backdrop_property() {
+    let css = js_sys::Reflect::get(&window(), &"CSS".into()).unwrap();
+    let Ok(register) = js_sys::Reflect::get(&css, &"registerProperty".into())
+        .and_then(|register| register.dyn_into::<js_sys::Function>())
+    else {
+        return;
+    };
+
+    let definition = js_sys::Object::new();
+    for (key, value) in [
+        ("name", JsValue::from(BACKDROP_OPACITY_PROPERTY)),
+        ("syntax", "<number>".into()),
+        ("inherits", true.into()),
+        ("initialValue", "1".into()),
+    ] {
+        js_sys::Reflect::set(&definition, &key.into(), &value).unwrap();
+    }
+
+    // Throws if the property has already been registered, which is fine.
+    _ = register.call1(&css, &definition);
+}
@@ -1,9 +1,17 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 use std::rc::Rc;
-
-use crate::{EnterAnimation, FadeAnimation, LeaveAnimation, MoveAnimation, SlidingAnimation};
+use std::time::Duration;
+
+use crate::animate::{
+    border_box_extent, keyframe_to_js, remove_styles_when_finished, restore_styles_on_end,
+};
+use crate::{
+    animate, animation_progress, wait_for, AnimateOptions, AnyResizeAnimation, EnterAnimation,
+    FadeAnimation, LeaveAnimation, MoveAnimation, SlidingAnimation, Timing,
+};
 use indexmap::IndexMap;
+use leptos::leptos_dom::helpers::TimeoutHandle;
 use leptos::leptos_dom::is_server;
 use leptos::*;
 use wasm_bindgen::closure::Closure;
@@ -13,21 +21,262 @@ use web_sys::{Animation, FillMode};
 
 use crate::position::{Extent, Position};
 
+/// How long after the expected end of a leave-animation the element gets removed anyway, in case
+/// the animation never finishes.
+const LEAVE_TIMEOUT_BUFFER: Duration = Duration::from_millis(100);
+
 /// Metadata for each item that's currently alive in the AnimatedFor.
 struct ItemMeta {
     /// Reference to the HTML element, if we found one
     el: Option<web_sys::HtmlElement>,
 
+    /// The rendered view. Used to find the element by walking the DOM in case it couldn't be
+    /// extracted from the view directly.
+    view: View,
+
     /// Reference to the scope which will be dropped when the item is removed.
     /// Used to prevent reactive state changes during the leave-animation.
     scope: Disposer,
 
-    /// The current animation that's running on the element.
-    /// We want to cancel this animation when we start a new one so that we don't have two running
-    /// at the same time.
-    cur_anim: Option<Animation>,
+    /// The current animations that are running on the element.
+    /// We want to cancel these animations when we start a new one so that we don't have two
+    /// running at the same time.
+    cur_anims: Vec<Animation>,
+
+    /// The most recent goal snapshot of the element. Used by [`AnimatedForHandle::relayout`] as
+    /// the starting point, since the layout has already changed by the time it is called.
+    snapshot: Option<ElementSnapshot>,
+
+    /// The animation that is waiting to be started in a later frame because of `batch_size`.
+    /// While it is waiting, the element is held in its starting state, see [`hold_anim_start`].
+    pending_job: Option<AnimHold>,
+}
+
+impl ItemMeta {
+    /// Find the element if it hasn't been found yet, or if it has been replaced since it was
+    /// found, for example by a `DynChild` whose content changed. Elements that are rendered
+    /// lazily may only be findable once the view has been mounted.
+    fn refresh_el(&mut self) {
+        if self.el.as_ref().map_or(true, |el| !el.is_connected()) {
+            self.el = find_first_element(&self.view).map(box_element);
+        }
+    }
+}
+
+/// The kind of animation that an item got in a transition, see [`TransitionRecord`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransitionKind {
+    Enter,
+    Move,
+    Leave,
+}
+
+/// Why an item plays its enter-animation, see `enter_anim_by_reason` on [`AnimatedFor`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnterReason {
+    /// The item was there when the component was first rendered (only with `appear`).
+    Appear,
+
+    /// The item was added to the list.
+    Add,
+
+    /// The item was re-added while it was still playing its leave-animation.
+    Resurrect,
+}
+
+/// Information about an entering item, see `enter_anim_by_context` on [`AnimatedFor`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EnterContext {
+    /// Why the item entered.
+    pub reason: EnterReason,
+
+    /// The index of the item in the list, after the change.
+    pub index: usize,
+
+    /// The number of items in the list, after the change (not counting leaving items).
+    pub count: usize,
+}
+
+impl EnterContext {
+    /// Whether the item is the first one in the list.
+    pub fn is_first(&self) -> bool {
+        self.index == 0
+    }
+
+    /// Whether the item is the last one in the list.
+    pub fn is_last(&self) -> bool {
+        self.index + 1 == self.count
+    }
+}
+
+/// Why an item plays its leave-animation, see `leave_anim_by_reason` on [`AnimatedFor`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeaveReason {
+    /// The item was removed while others stayed in the list.
+    Remove,
+
+    /// All items that were in the list were removed at once, for example because the list was
+    /// cleared or replaced with new data.
+    RemoveAll,
+}
+
+/// How [`AnimatedFor`] measures the positions of the items, see `position_source`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PositionSource {
+    /// `offsetLeft` / `offsetTop`: the layout position relative to the `offsetParent`. It
+    /// ignores all transforms (of the element and of its ancestors) and is rounded to whole
+    /// pixels, but it is stable while the elements are being animated.
+    #[default]
+    Offset,
+
+    /// The difference between the bounding rects of the element and its `offsetParent`. It has
+    /// subpixel precision and includes the transforms of the element, so interrupted animations
+    /// are measured where they are on screen. In a transformed (for example scaled) ancestor, the
+    /// positions are in screen pixels instead of the ancestor's coordinates, which
+    /// `compensate_scale` of [`AnimatedFor`] converts back for scaled ancestors.
+    BoundingRect,
+}
+
+/// How [`AnimatedFor`] animates a change where none of the previous items remain in the list, see
+/// `replace_mode`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReplaceMode {
+    /// The old items leave while the new ones enter.
+    #[default]
+    Simultaneous,
+
+    /// The new items only start entering once the old ones have left. They keep their place in
+    /// the layout but are hidden until then.
+    Sequential,
+}
+
+/// Record of the animation of a single item in a transition, see `debug_record` on
+/// [`AnimatedFor`].
+#[derive(Clone, Debug)]
+pub struct TransitionRecord<K> {
+    pub key: K,
+    pub kind: TransitionKind,
+
+    /// The snapshot before the change. Only set for entering items if they slide in (see
+    /// `slide_enter`), in which case it is the position they slide in from.
+    pub from: Option<ElementSnapshot>,
+
+    /// The snapshot after the change. Not set for leaving items.
+    pub to: Option<ElementSnapshot>,
+
+    /// The duration of the animation, if it has already been started.
+    pub duration: Option<Duration>,
+}
+
+/// An animation that is about to be started for an item.
+#[derive(Clone, Copy)]
+enum AnimJob {
+    /// Enter-animation, optionally sliding in from the given snapshot (see `slide_enter`).
+    Enter {
+        slide_from: Option<ElementSnapshot>,
+        to: ElementSnapshot,
+        context: EnterContext,
+    },
+
+    /// Move-animation. `reorder` is set if the move is part of a reorder, see
+    /// `reorder_move_anim`.
+    Move {
+        from: ElementSnapshot,
+        to: ElementSnapshot,
+        reorder: bool,
+    },
+}
+
+/// An [`AnimJob`] that is waiting to be started while its element is held in the starting state,
+/// see [`hold_anim_start`].
+struct AnimHold {
+    job: AnimJob,
+
+    /// The inline value and priority of the property that is used to hold the element, from
+    /// before it got overwritten. Restored by [`release_anim_hold`].
+    prev_style: (String, String),
+}
+
+/// Handle to control an [`AnimatedFor`] from the outside. Create one using
+/// [`AnimatedForHandle::new`] and pass it to the `handle` prop.
+pub struct AnimatedForHandle<K: 'static> {
+    relayout: StoredValue<Option<Callback<()>>>,
+    leaving_keys: RwSignal<HashSet<K>>,
+    current_animation: StoredValue<Option<Callback<K, Option<Animation>>>>,
+}
+
+impl<K: Eq + Hash + Clone + 'static> AnimatedForHandle<K> {
+    pub fn new() -> Self {
+        Self {
+            relayout: StoredValue::new(None),
+            leaving_keys: RwSignal::new(HashSet::new()),
+            current_animation: StoredValue::new(None),
+        }
+    }
+
+    /// Whether the item with the given key is currently playing its leave-animation. This is
+    /// reactive, so it can be used to disable interactions with items that are on their way out.
+    ///
+    /// ```
+    /// <button on:click=on_delete disabled=move || handle.is_leaving(&id)>"Delete"</button>
+    /// ```
+    pub fn is_leaving(&self, key: &K) -> bool {
+        self.leaving_keys
+            .with(|leaving_keys| leaving_keys.contains(key))
+    }
+
+    /// Animate all items from their last known positions to their current ones, without any
+    /// enter or leave animations. Use this when the layout changed for reasons outside of `each`,
+    /// for example because a font loaded or a sibling element was toggled.
+    ///
+    /// The new positions are measured in a microtask, so this can be called right after making
+    /// the change. Because it runs after the move phase of any pending `each` change, items that
+    /// already got animated by that are at their recorded positions and won't be animated twice.
+    /// Items that are still moving are restarted from their last recorded goal position.
+    pub fn relayout(&self) {
+        if let Some(relayout) = self.relayout.get_value() {
+            relayout(());
+        }
+    }
+
+    /// The animation that was most recently started on the item with the given key, if any. It
+    /// may already have finished.
+    pub fn current_animation(&self, key: &K) -> Option<Animation> {
+        self.current_animation
+            .get_value()
+            .and_then(|current_animation| current_animation(key.clone()))
+    }
+
+    /// The progress of the item's current animation as a signal, see [`animation_progress`]. It
+    /// follows the animation that is running when this is called, so call it again for the next
+    /// one, for example from `on_enter_start` in a microtask. Returns 1 if nothing is running.
+    ///
+    /// ```
+    /// let progress = handle.progress(&id);
+    /// view! { <div class="bar" style:width=move || format!("{}%", progress.get() * 100.0) /> }
+    /// ```
+    pub fn progress(&self, key: &K) -> Signal<f64> {
+        match self.current_animation(key) {
+            Some(anim) => animation_progress(&anim),
+            None => Signal::derive(|| 1.0),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone + 'static> Default for AnimatedForHandle<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: 'static> Clone for AnimatedForHandle<K> {
+    fn clone(&self) -> Self {
+        *self
+    }
 }
 
+impl<K: 'static> Copy for AnimatedForHandle<K> {}
+
 /// Keyframe for the FLIP animation.
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -42,54 +291,106 @@ struct MoveAnimKeyframe {
     /// Only set if `animate_size` is true
     #[serde(skip_serializing_if = "Option::is_none")]
     height: Option<String>,
-}
 
-/// Wrapper around the `animate` function in the Web Animations API because in web_sys it is still
-/// unstable and that causes some problems with cranelift.
-pub fn animate(
-    el: &web_sys::HtmlElement,
-    keyframes: Option<&js_sys::Object>,
-    duration: &::wasm_bindgen::JsValue,
-    fill_mode: FillMode,
-    easing: Option<impl AsRef<str>>,
-) -> Animation {
-    #[cfg(not(feature = "ssr"))]
-    {
-        use web_sys::KeyframeAnimationOptions;
-        let mut options = KeyframeAnimationOptions::new();
-
-        options.duration(duration).fill(fill_mode);
+    /// Only set for the waypoints of the move animation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<f64>,
 
-        if let Some(easing) = easing {
-            options.easing(easing.as_ref());
-        }
+    /// Only set if the element shrinks and `clip_on_shrink` is true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    overflow: Option<&'static str>,
 
-        el.animate_with_keyframe_animation_options(keyframes, &options)
-    }
-    #[cfg(feature = "ssr")]
-    {
-        _ = el;
-        _ = keyframes;
-        _ = duration;
-        _ = fill_mode;
-        _ = easing;
-        unimplemented!("Animation API can't be run on the server")
-    }
+    /// Only set for SVG elements, whose transforms are relative to the `<svg>` by default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transform_box: Option<&'static str>,
 }
 
 /// A snapshot of an element's position and size at a specific moment.
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct ElementSnapshot {
     /// The position of the element.
-    position: Position,
+    pub(crate) position: Position,
 
     /// The height and width of the element.
-    extent: Extent,
+    pub(crate) extent: Extent,
+}
+
+impl ElementSnapshot {
+    /// Measure the element the same way [`AnimatedFor`] does (with its default settings), for
+    /// building custom animated components. The position is relative to the offset parent of the
+    /// element and doesn't include its transforms, so that it can be used for a FLIP animation
+    /// even while the element is being animated. The size is only measured if `record_size` is
+    /// set, since that forces a more expensive measurement.
+    ///
+    /// Compare two snapshots with `==` to find out if anything changed, and use
+    /// [`Self::offset_from`] for the translation of a FLIP animation.
+    ///
+    /// # Example
+    /// ```
+    /// let before = ElementSnapshot::measure(&el, false);
+    /// // ... change the layout ...
+    /// let after = ElementSnapshot::measure(&el, false);
+    ///
+    /// if before != after {
+    ///     let offset = before.offset_from(&after);
+    ///     // ... animate from `translate(offset.x, offset.y)` to `none` ...
+    /// }
+    /// ```
+    pub fn measure(el: &web_sys::HtmlElement, record_size: bool) -> Self {
+        get_el_snapshot(el, record_size, false, None, None, true)
+    }
+
+    /// Like [`Self::measure`], but ignores the element's margins, like the `handle_margins`
+    /// setting of [`AnimatedFor`]. The margins are removed while the element is measured, which
+    /// forces an additional layout.
+    pub fn measure_ignoring_margins(el: &web_sys::HtmlElement, record_size: bool) -> Self {
+        get_el_snapshot(el, record_size, true, None, None, true)
+    }
+
+    /// How far the element has to be moved from `other` to get to this snapshot's position.
+    pub fn offset_from(&self, other: &ElementSnapshot) -> Position {
+        self.position - other.position
+    }
+
+    /// The position of the element, relative to its offset parent.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// The size of the element. Only measured if `animate_size` is enabled, otherwise zero.
+    pub fn extent(&self) -> Extent {
+        self.extent
+    }
+}
+
+/// Keyframe for the counter-scale that keeps the children of an element undistorted while the
+/// element itself is scaled. See `scale_size` on [`AnimatedFor`].
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CounterScaleKeyframe {
+    transform_origin: &'static str,
+    transform: String,
+    offset: f64,
+}
+
+/// Number of keyframes for the counter-scale. The inverse of a linearly interpolated scale isn't
+/// linear, so it needs to be sampled.
+const COUNTER_SCALE_STEPS: usize = 10;
+
+/// Keyframe for collapsing the cells of a leaving table row. The start of the animation is
+/// implicitly taken from the cell's current style.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CollapseCellKeyframe {
+    padding_top: &'static str,
+    padding_bottom: &'static str,
+    line_height: &'static str,
+    font_size: &'static str,
 }
 
 /// Wrapper trait for [`EnterAnimation`] to be used as a dyn trait. The original trait is not
 /// object-safe because it has an associated type.
-trait EnterAnimationHandler {
+pub(crate) trait EnterAnimationHandler {
     /// Run the enter-animation. The returned `Animation` may be used to cancel the animation later
     /// as well as to trigger a callback when the animation finishes.
     fn animate(&self, el: &web_sys::HtmlElement) -> Animation;
@@ -98,22 +399,31 @@ trait EnterAnimationHandler {
 /// Automatically implemented on all `EnterAnimation`s.
 impl<T: EnterAnimation> EnterAnimationHandler for T {
     fn animate(&self, el: &web_sys::HtmlElement) -> Animation {
-        let r = self.enter();
+        let r = self.enter(el);
 
         // Build the JavaScript object from the animations keyframes.
         let arr: Array = r
             .keyframes
             .into_iter()
-            .map(|v| serde_wasm_bindgen::to_value(&v).unwrap())
+            .skip((r.from_current && r.keyframes.len() > 1) as usize)
+            .map(|v| keyframe_to_js(&v))
             .collect();
 
+        // A delayed element would otherwise show up in its final state until the animation
+        // starts.
+        let fill = if r.timing.delay.is_zero() {
+            FillMode::None
+        } else {
+            FillMode::Backwards
+        };
+
         animate(
-            &el,
+            el,
             Some(&arr.into()),
-            &(r.duration.as_secs_f64() * 1000.0).into(),
-            // The fill mode can shadow timing bugs, so we avoid it as much as possible.
-            FillMode::None,
-            r.timing_fn.as_ref().map(|v| v.as_str()),
+            &AnimateOptions {
+                pseudo_element: r.pseudo_element,
+                ..r.timing.to_options().fill(fill)
+            },
         )
     }
 }
@@ -132,44 +442,93 @@ impl<T: EnterAnimationHandler + 'static> From<T> for AnyEnterAnimation {
     }
 }
 
+impl AnyEnterAnimation {
+    /// Run the enter-animation on an element that is managed outside of [`AnimatedFor`].
+    pub(crate) fn animate(&self, el: &web_sys::HtmlElement) -> Animation {
+        self.anim.animate(el)
+    }
+}
+
 /// Wrapper trait for [`LeaveAnimation`] to be used as a dyn trait. The original trait is not
 /// object-safe because it has an associated type.
-trait LeaveAnimationHandler {
-    fn animate(&self, el: &web_sys::HtmlElement) -> Animation;
+pub(crate) trait LeaveAnimationHandler {
+    /// Run the leave-animation. Also returns the options that the animation was started with, so
+    /// that accompanying animations can use the same timing.
+    fn animate(&self, el: &web_sys::HtmlElement) -> (Animation, AnimateOptions);
+
+    /// The CSS properties that the leave-animation continues from the element's current style,
+    /// see `AnimationConfig::from_current`.
+    fn from_current_properties(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 /// Automatically implemented on all `LeaveAnimation`s.
 impl<T: LeaveAnimation> LeaveAnimationHandler for T {
-    fn animate(&self, el: &web_sys::HtmlElement) -> Animation {
+    fn animate(&self, el: &web_sys::HtmlElement) -> (Animation, AnimateOptions) {
         let r = self.leave();
 
         // Build the JavaScript object from the animations keyframes.
         let arr: Array = r
             .keyframes
             .into_iter()
-            .map(|v| serde_wasm_bindgen::to_value(&v).unwrap())
+            .skip((r.from_current && r.keyframes.len() > 1) as usize)
+            .map(|v| keyframe_to_js(&v))
             .collect();
 
-        animate(
-            &el,
-            Some(&arr.into()),
-            &(r.duration.as_secs_f64() * 1000.0).into(),
-            FillMode::None,
-            r.timing_fn.as_ref().map(|v| v.as_str()),
-        )
+        let options = AnimateOptions {
+            pseudo_element: r.pseudo_element,
+            ..r.timing.to_options()
+        };
+
+        (animate(el, Some(&arr.into()), &options), options)
+    }
+
+    fn from_current_properties(&self) -> Vec<String> {
+        use wasm_bindgen::JsCast;
+
+        let r = self.leave();
+        let Some(first) = r.keyframes.first().filter(|_| r.from_current) else {
+            return Vec::new();
+        };
+
+        let first = keyframe_to_js(first);
+        js_sys::Object::keys(first.unchecked_ref())
+            .iter()
+            .filter_map(|key| key.as_string())
+            .filter(|key| !["offset", "easing", "composite"].contains(&key.as_str()))
+            .map(|key| {
+                // The keyframes use the camelCase names, but the style needs the CSS ones.
+                key.chars().fold(String::new(), |mut name, c| {
+                    if c.is_ascii_uppercase() {
+                        name.push('-');
+                    }
+                    name.push(c.to_ascii_lowercase());
+                    name
+                })
+            })
+            .collect()
     }
 }
 
 /// Any struct that implements [`LeaveAnimation`] can be converted into this using `into()`.
 /// The props on the various components will do this automatically.
+#[derive(Clone)]
 pub struct AnyLeaveAnimation {
-    anim: Box<dyn LeaveAnimationHandler>,
+    anim: Rc<dyn LeaveAnimationHandler>,
 }
 
 /// Any [`LeaveAnimation`] can be converted to an [`AnyLeaveAnimation`] using the intermediate dyn Trait.
 impl<T: LeaveAnimationHandler + 'static> From<T> for AnyLeaveAnimation {
     fn from(v: T) -> Self {
-        AnyLeaveAnimation { anim: Box::new(v) }
+        AnyLeaveAnimation { anim: Rc::new(v) }
+    }
+}
+
+impl AnyLeaveAnimation {
+    /// Run the leave-animation on an element that is managed outside of [`AnimatedFor`].
+    pub(crate) fn animate(&self, el: &web_sys::HtmlElement) -> (Animation, AnimateOptions) {
+        self.anim.animate(el)
     }
 }
 
@@ -181,68 +540,358 @@ trait MoveAnimationHandler {
         el: &web_sys::HtmlElement,
         prev_snapshot: ElementSnapshot,
         new_snapshot: ElementSnapshot,
-        animate_size: bool,
+        settings: MoveAnimSettings,
     ) -> Animation;
 }
 
+/// Settings from the [`AnimatedFor`] props that affect how move animations are built.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct MoveAnimSettings {
+    /// See `animate_size` on [`AnimatedFor`].
+    pub(crate) animate_size: bool,
+
+    /// If set, the translation is rounded to whole device pixels of this pixel ratio.
+    pub(crate) device_pixel_ratio: Option<f64>,
+
+    /// See `use_translate3d` on [`AnimatedFor`].
+    pub(crate) use_translate3d: bool,
+
+    /// See `scale_size` on [`AnimatedFor`].
+    pub(crate) scale_size: bool,
+
+    /// See `clip_on_shrink` on [`AnimatedFor`].
+    pub(crate) clip_on_shrink: bool,
+}
+
 impl<T: MoveAnimation> MoveAnimationHandler for T {
     fn animate(
         &self,
         el: &web_sys::HtmlElement,
         prev_snapshot: ElementSnapshot,
         new_snapshot: ElementSnapshot,
-        animate_size: bool,
+        settings: MoveAnimSettings,
     ) -> Animation {
+        use wasm_bindgen::JsCast;
+
         let r = self.animate(prev_snapshot, new_snapshot);
 
-        let diff = prev_snapshot.position - new_snapshot.position;
+        let MoveAnimSettings {
+            animate_size,
+            device_pixel_ratio,
+            use_translate3d,
+            scale_size,
+            clip_on_shrink,
+        } = settings;
+
+        // In the scale mode, the size change is animated using a transform instead of width and
+        // height.
+        let scale = (animate_size && scale_size).then(|| {
+            let ratio = |prev: f64, new: f64| if new == 0.0 { 1.0 } else { prev / new };
+            Position {
+                x: ratio(prev_snapshot.extent.width, new_snapshot.extent.width),
+                y: ratio(prev_snapshot.extent.height, new_snapshot.extent.height),
+            }
+        });
+        let animate_size = animate_size && !scale_size;
+
+        // The contents keep their full size while the box shrinks, so they would overflow.
+        let overflow = (animate_size
+            && clip_on_shrink
+            && (new_snapshot.extent.width < prev_snapshot.extent.width
+                || new_snapshot.extent.height < prev_snapshot.extent.height))
+            .then_some("hidden");
+
+        // Makes `transform-origin` and the scale refer to the element itself, like for HTML.
+        let transform_box = is_svg(el).then_some("fill-box");
+
+        // Translating by fractions of a device pixel makes text blurry during the move.
+        let round = |v: f64| match device_pixel_ratio {
+            Some(dpr) => (v * dpr).round() / dpr,
+            None => v,
+        };
+        let translate = |diff: Position| {
+            if use_translate3d {
+                format!("translate3d({}px, {}px, 0)", diff.x, diff.y)
+            } else {
+                format!("translate({}px, {}px)", diff.x, diff.y)
+            }
+        };
+
+        // With a separate vertical timing, the axis that ends first runs as its own animation.
+        // If the element only moves along one axis, the timing of that axis is used for
+        // everything.
+        let start_diff = prev_snapshot.position - new_snapshot.position;
+        let moves_x = round(start_diff.x) != 0.0;
+        let moves_y = round(start_diff.y) != 0.0;
+        let (timing, split_axis) = match r.vertical_timing {
+            Some(vertical) if moves_x && moves_y => {
+                let end = |timing: &Timing| timing.delay + timing.duration;
+                if end(&vertical) > end(&r.timing) {
+                    (vertical, Some((Axis::Horizontal, r.timing)))
+                } else {
+                    (r.timing, Some((Axis::Vertical, vertical)))
+                }
+            }
+            Some(vertical) if moves_y => (vertical, None),
+            _ => (r.timing, None),
+        };
+
+        // The transform that places the element at `position` (in the coordinates of the
+        // snapshots) at the given progress of the size change.
+        let transform_at = |position: Position, progress: f64| {
+            let mut diff = position - new_snapshot.position;
+            match split_axis {
+                Some((Axis::Horizontal, _)) => diff.x = 0.0,
+                Some((Axis::Vertical, _)) => diff.y = 0.0,
+                None => {}
+            }
+
+            let mut transform = translate(Position {
+                x: round(diff.x),
+                y: round(diff.y),
+            });
+
+            if let Some(scale) = scale {
+                let scale = Position::lerp(scale, Position { x: 1.0, y: 1.0 }, progress);
+                transform += &format!(" scale({}, {})", scale.x, scale.y);
+            }
+
+            transform
+        };
+
+        let transform_origin = r.transform_origin.as_deref().unwrap_or("top left");
+
+        let waypoints = r.waypoints.iter().map(|waypoint| {
+            let mut transform = transform_at(waypoint.position, waypoint.offset);
+            if waypoint.rotation != 0.0 {
+                transform += &format!(" rotate({}deg)", waypoint.rotation);
+            }
+
+            serde_wasm_bindgen::to_value(&MoveAnimKeyframe {
+                transform_origin: waypoint
+                    .transform_origin
+                    .as_deref()
+                    .unwrap_or(transform_origin)
+                    .to_string(),
+                transform,
+                width: None,
+                height: None,
+                offset: Some(waypoint.offset),
+                overflow,
+                transform_box,
+            })
+            .unwrap()
+        });
 
-        // Build the JavaScript object. Move Animations don't support keyframes yet.
-        let arr: Array = [
+        // Build the JavaScript object.
+        let arr: Array = std::iter::once(
             serde_wasm_bindgen::to_value(&MoveAnimKeyframe {
-                transform_origin: "top left".to_string(),
-                transform: format!("translate({}px, {}px)", diff.x, diff.y),
+                transform_origin: transform_origin.to_string(),
+                transform: transform_at(prev_snapshot.position, 0.0),
                 width: animate_size.then(|| format!("{}px", prev_snapshot.extent.width)),
                 height: animate_size.then(|| format!("{}px", prev_snapshot.extent.height)),
+                offset: None,
+                overflow,
+                transform_box,
             })
             .unwrap(),
+        )
+        .chain(waypoints)
+        .chain(std::iter::once(
             serde_wasm_bindgen::to_value(&MoveAnimKeyframe {
-                transform_origin: "top left".to_string(),
+                transform_origin: transform_origin.to_string(),
                 transform: "none".to_string(),
                 width: animate_size.then(|| format!("{}px", new_snapshot.extent.width)),
                 height: animate_size.then(|| format!("{}px", new_snapshot.extent.height)),
+                offset: None,
+                overflow,
+                transform_box,
             })
             .unwrap(),
-        ]
-        .into_iter()
+        ))
         .collect();
 
-        animate(
-            &el,
-            Some(&arr.into()),
-            &(r.duration.as_secs_f64() * 1000.0).into(),
-            FillMode::None,
-            r.timing_fn.as_ref().map(|v| v.as_str()),
-        )
+        // The split-off axis has to be started first, so that the main animation is added on
+        // top of it instead of being shifted by the scale.
+        let split_anim = split_axis.map(|(axis, timing)| {
+            let diff = match axis {
+                Axis::Horizontal => Position {
+                    x: round(start_diff.x),
+                    y: 0.0,
+                },
+                Axis::Vertical => Position {
+                    x: 0.0,
+                    y: round(start_diff.y),
+                },
+            };
+
+            let keyframes: Array = [translate(diff), "none".to_string()]
+                .into_iter()
+                .map(|transform| {
+                    serde_wasm_bindgen::to_value(&MoveAnimKeyframe {
+                        transform_origin: "top left".to_string(),
+                        transform,
+                        width: None,
+                        height: None,
+                        offset: None,
+                        overflow: None,
+                        transform_box,
+                    })
+                    .unwrap()
+                })
+                .collect();
+
+            // Holds the axis in place while it waits for its turn.
+            let options = timing.to_options().fill(FillMode::Backwards);
+            animate(el, Some(&keyframes.into()), &options)
+        });
+
+        let options = timing.to_options();
+        let anim = if split_anim.is_some() {
+            let options = options.clone().composite(web_sys::CompositeOperation::Add);
+            animate(el, Some(&arr.into()), &options)
+        } else {
+            animate(el, Some(&arr.into()), &options)
+        };
+
+        if let Some(split_anim) = split_anim {
+            let closure = Closure::<dyn Fn(web_sys::Event)>::new(move |_| split_anim.cancel())
+                .into_js_value();
+            anim.add_event_listener_with_callback("cancel", closure.unchecked_ref())
+                .unwrap();
+        }
+
+        if let Some(scale) = scale {
+            counter_scale_children(el, scale, &options, &anim);
+        }
+
+        anim
     }
 }
 
+/// One of the two axes of a move, see [`AnimationConfigMove::vertical_timing`].
+#[derive(Clone, Copy, Debug)]
+enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+/// Apply the inverse of `scale` to the direct children of the element, so that they keep their
+/// size while the element is scaled. The counter-animations get cancelled together with `anim`.
+fn counter_scale_children(
+    el: &web_sys::HtmlElement,
+    scale: Position,
+    options: &AnimateOptions,
+    anim: &Animation,
+) {
+    use wasm_bindgen::JsCast;
+
+    let keyframes: Array = (0..=COUNTER_SCALE_STEPS)
+        .map(|i| {
+            let t = i as f64 / COUNTER_SCALE_STEPS as f64;
+            let scale = Position::lerp(scale, Position { x: 1.0, y: 1.0 }, t);
+
+            serde_wasm_bindgen::to_value(&CounterScaleKeyframe {
+                transform_origin: "top left",
+                transform: format!("scale({}, {})", 1.0 / scale.x, 1.0 / scale.y),
+                offset: t,
+            })
+            .unwrap()
+        })
+        .collect();
+    let keyframes: js_sys::Object = keyframes.into();
+
+    let children = el.children();
+    let child_anims = (0..children.length())
+        .filter_map(|i| children.item(i)?.dyn_into::<web_sys::HtmlElement>().ok())
+        .map(|child| animate(&child, Some(&keyframes), options))
+        .collect::<Vec<_>>();
+
+    let closure = Closure::<dyn Fn(web_sys::Event)>::new(move |_| {
+        for child_anim in &child_anims {
+            child_anim.cancel();
+        }
+    })
+    .into_js_value();
+
+    anim.set_oncancel(Some(&closure.into()));
+}
+
 /// Any struct that implements [`MoveAnimation`] can be converted into this using `into()`.
+#[derive(Clone)]
 pub struct AnyMoveAnimation {
-    anim: Box<dyn MoveAnimationHandler>,
+    anim: Rc<dyn MoveAnimationHandler>,
+}
+
+impl AnyMoveAnimation {
+    /// Run the move-animation on an element that is managed outside of [`AnimatedFor`].
+    pub(crate) fn animate(
+        &self,
+        el: &web_sys::HtmlElement,
+        from: ElementSnapshot,
+        to: ElementSnapshot,
+        settings: MoveAnimSettings,
+    ) -> Animation {
+        self.anim.animate(el, from, to, settings)
+    }
 }
 
 /// Any [`MoveAnimation`] can be converted to an [`AnyMoveAnimation`] using the intermediate
 /// dyn Trait.
 impl<T: MoveAnimationHandler + 'static> From<T> for AnyMoveAnimation {
     fn from(v: T) -> Self {
-        AnyMoveAnimation { anim: Box::new(v) }
+        AnyMoveAnimation { anim: Rc::new(v) }
     }
 }
 
+/// Adapts a `children` function that takes owned items, like the one of
+/// [`<For />`][leptos::For], to the one of [`AnimatedFor`], which only gets references. This
+/// makes it easier to switch existing code over.
+///
+/// Each item gets cloned once whenever its view is created, which is when it enters (or is
+/// re-added after leaving). For items that are expensive to clone, consider wrapping them in an
+/// `Rc` or taking the reference directly instead.
+///
+/// # Example
+/// ```
+/// <AnimatedFor
+///     each=move || todos.get()
+///     key=|todo| todo.id
+///     children=owned_children(|todo: Todo| view! { <TodoItem todo /> })
+/// />
+/// ```
+pub fn owned_children<T: Clone, N>(children: impl Fn(T) -> N) -> impl Fn(&T) -> N {
+    move |item| children(item.clone())
+}
+
 /// A version of the [`<For />`][leptos::For] component that animates children when they enter or
 /// leave, as well as moving them around when their position changes.
 ///
+/// The items, keys and closures only need to be `'static`; none of them have to be `Send` or
+/// `Sync`, so items holding an `Rc` or other non-thread-safe data work as well.
+///
+/// # Server-side rendering
+/// Nothing is animated on the server. At rest, `AnimatedFor` doesn't add any inline styles or
+/// classes to the items: the animations don't fill, and the styles that are set for a
+/// leave-animation only exist on elements that are about to be removed. The server-rendered HTML
+/// therefore already matches the resting state on the client, and there is nothing to emit or to
+/// clear during hydration. The first snapshots are taken from the hydrated DOM when `each` first
+/// changes, so they don't depend on anything computed on the server. Only `appear` animates
+/// content that the server has already shown.
+///
+/// # SVG
+/// Elements inside of an `<svg>` (such as `<rect>`, `<circle>`, `<g>` or `<text>`) can be
+/// animated as well. They are measured relative to the `<svg>` they are in and get
+/// `transform-box: fill-box` during move-animations. Some limitations apply:
+/// - The translation is in user units, so the `<svg>` must not scale its contents using a
+///   `viewBox` that differs from its size.
+/// - SVG has no layout, so the items only move if their position (for example the `x` attribute)
+///   depends on their index. Leaving items can't be taken out of the layout either, so use
+///   `keep_in_flow`.
+/// - `animate_size` only works with `scale_size`, since `width` and `height` are attributes on
+///   most SVG elements.
+/// - An `<svg>` itself can't be an item; wrap it in an HTML element instead.
+///
 /// # Example
 /// ```
 /// #[component]
@@ -305,6 +954,13 @@ pub fn AnimatedFor<IF, I, T, EF, N, KF, K>(
     each: IF,
 
     /// A function that returns a key that is unique for each item currently in the list.
+    ///
+    /// The key function is reactive: it runs inside the same effect as `each`, so if it reads
+    /// a signal (for example to switch between keying by id and keying by name), changing that
+    /// signal re-diffs the list without remounting the component. Items whose key exists both
+    /// before and after the change are treated as the same item and move to their new position,
+    /// all others leave or enter. Note that the children of items that keep their key are not
+    /// re-rendered, even if the item itself changed.
     key: KF,
 
     /// A function that receives a reference to the item and returns the view to render it.
@@ -313,17 +969,26 @@ pub fn AnimatedFor<IF, I, T, EF, N, KF, K>(
     ///
     /// **Please note**, unlike the [`<For />`][leptos::For] component, this only gets a reference,
     /// not the original value. If you need to take ownership of the item, you need to clone or
-    /// copy it.
+    /// copy it, for example using [`owned_children`].
     ///
-    /// The returned View must have a DOM node as its top level element, or a component that does.
-    /// Due to the way leptos works, we cannot currently extract node-refs from other elements such
-    /// as `Suspense`, `DynChild`, `Each`, etc. Also Fragments/Components that return multiple
-    /// elements will only have their first element animated.
+    /// The returned View should have a DOM node as its top level element, or a component that
+    /// does. For other views such as `DynChild` or `Each`, we fall back to searching the rendered
+    /// DOM nodes for the first HTML element, which may only succeed once the view is mounted.
+    /// Items for which no element could be found are not animated. Also Fragments/Components that
+    /// return multiple elements will only have their first element animated. Elements with
+    /// `display:contents` have no box that could be animated, so their first child element is
+    /// animated instead.
     ///
     /// The elements should be able to handle being set to `position:absolute` during the
     /// leave-animation, although it will fix their size in place (so for example an element with
     /// `width:100%` will still work). Ideally the elements should also be block-like elements
     /// without margins.
+    ///
+    /// Custom elements (web components) work like any other element, since the animations only
+    /// touch the host element and not its shadow DOM. However, they are `display:inline` unless
+    /// their styles say otherwise, and inline elements can't be transformed, so they wouldn't
+    /// move. Give them a `display` such as `block`, for example using `:host { display: block }`.
+    /// Debug builds warn about inline custom elements.
     children: EF,
 
     /// Callback that is called for each item when it is about to start its leaving animation
@@ -331,26 +996,95 @@ pub fn AnimatedFor<IF, I, T, EF, N, KF, K>(
     /// same time when `each` changes, for example if you want to apply a counter-animation. Note
     /// that leaving items are set to `position:absolute`.
     ///
+    /// The callbacks receive a `web_sys::Element`, since the items inside of an `<svg>` are SVG
+    /// elements. Cast it using `dyn_into::<web_sys::HtmlElement>()` to access HTML-only APIs.
+    ///
+    /// **Migration:** the callbacks used to receive a `web_sys::HtmlElement`.
+    ///
     /// See also [`AnimatedLayout`][crate::AnimatedLayout].
     #[prop(optional)]
-    on_leave_start: Option<Callback<(web_sys::HtmlElement, Position)>>,
+    on_leave_start: Option<Callback<(web_sys::Element, Position)>>,
 
     /// See `on_leave_start`.
     #[prop(optional)]
-    on_enter_start: Option<Callback<web_sys::HtmlElement>>,
+    on_enter_start: Option<Callback<web_sys::Element>>,
+
+    /// Callback that is called for each item when its leave-animation has finished, right before
+    /// it is removed. It is not called if the animation got interrupted, for example because the
+    /// item was re-added while it was still leaving.
+    #[prop(optional)]
+    on_leave_end: Option<Callback<web_sys::Element>>,
+
+    /// Callback that is called for each item when its enter-animation has finished. It is not
+    /// called if the animation got interrupted, for example because the item was removed again or
+    /// moved before the animation could finish.
+    #[prop(optional)]
+    on_enter_end: Option<Callback<web_sys::Element>>,
 
     /// Callback that is called after the initial snapshots of all elements have been taken but
     /// before the goal snapshots are taken. This is the time to apply CSS changes to the elements
     /// or to the container and have the elements be able to animate to their new positions.
+    ///
+    /// The phase callbacks of a transition are called in this order:
+    /// 1. `on_after_snapshot`: synchronously when `each` changes, before the DOM is updated.
+    /// 2. `on_after_dom_update`: in a microtask, once the new items have been rendered but before
+    ///    any goal snapshots are taken.
+    /// 3. `on_after_goal_snapshot`: in the same microtask, after the goal snapshots have been
+    ///    taken and before any animations are started.
+    /// 4. `on_transition_end`: once the animations have finished.
+    ///
+    /// Except for `on_after_snapshot` and `on_after_dom_update`, they are not called on the
+    /// initial render unless `appear` is set, since nothing gets animated then.
     #[prop(optional)]
     on_after_snapshot: Option<Callback<()>>,
 
+    /// Callback that is called once the DOM has been updated, before the goal snapshots are
+    /// taken. Layout changes made here are part of the animated goal. See `on_after_snapshot`
+    /// for the ordering.
+    #[prop(optional)]
+    on_after_dom_update: Option<Callback<()>>,
+
+    /// Callback that is called after the goal snapshots have been taken, right before the
+    /// animations start. External animations (for example on a canvas) that start here start in
+    /// the same frame as the ones of the items. See `on_after_snapshot` for the ordering.
+    #[prop(optional)]
+    on_after_goal_snapshot: Option<Callback<()>>,
+
+    /// Callback that is called once all animations that were running when the transition
+    /// started have finished or got cancelled, including the leave-animations. Animations that
+    /// are deferred by `batch_size` or `replace_mode` are not waited for. A transition that
+    /// interrupts this one cancels its animations, so this is called early in that case. See
+    /// `on_after_snapshot` for the ordering.
+    #[prop(optional)]
+    on_transition_end: Option<Callback<()>>,
+
     /// Whether enter animations play when the component is initially rendered. This is usually not
     /// what you want. On SSR this will cause visual glitches because the enter animation would
-    /// start much later than the initial render.
+    /// start much later than the initial render, unless `ssr_appear` is set as well.
     #[prop(default = false)]
     appear: bool,
 
+    /// Together with `appear`, makes the appear animations work with SSR: the server renders the
+    /// items with an inline `visibility:hidden`, which is removed right before their enter
+    /// animations start after hydration. The items are never shown fully rendered before they
+    /// appear, but they also stay hidden if the page doesn't hydrate (for example without
+    /// JavaScript).
+    ///
+    /// The enter animation should start from an invisible state (like [`FadeAnimation`]), and it
+    /// shouldn't have a delay, since the items are visible as soon as it has been started.
+    /// Only items whose view is an element, or a component whose first child is one, can be
+    /// hidden.
+    #[prop(default = false)]
+    ssr_appear: bool,
+
+    /// Whether entering elements should also slide into their slot together with the elements
+    /// that move to make room for them, instead of only playing the enter animation in place.
+    ///
+    /// The entering element starts offset by the same distance that its closest existing neighbor
+    /// moved and uses the `move_anim` to get to its position.
+    #[prop(default = false)]
+    slide_enter: bool,
+
     /// Whether to also animate the sizes of the elements for move animations, for example in a
     /// grid with differently sized columns or rows.
     ///
@@ -363,6 +1097,24 @@ pub fn AnimatedFor<IF, I, T, EF, N, KF, K>(
     #[prop(default = false)]
     animate_size: bool,
 
+    /// Whether `animate_size` should scale the elements using `transform` instead of animating
+    /// their `width` and `height`. This runs on the compositor and doesn't reflow the contents
+    /// during the animation, but it would distort them, so the direct children of each element get
+    /// the inverse scale applied to keep them undistorted.
+    ///
+    /// For this to work, each element should have a single wrapper element as its only child that
+    /// contains the actual content, and the element itself should only draw its background.
+    /// Borders and rounded corners are still distorted.
+    #[prop(default = false)]
+    scale_size: bool,
+
+    /// Whether `animate_size` should set `overflow: hidden` on elements while they shrink, so that
+    /// their contents, which already have their final layout but are still at their full size,
+    /// get clipped instead of overflowing the shrinking box. The overflow goes back to normal once
+    /// the animation has finished. Has no effect with `scale_size`.
+    #[prop(default = false)]
+    clip_on_shrink: bool,
+
     /// Whether the child elements can have margins applied. This will simply remove the margins
     /// during the snapshotting process for element positions and then reapply them, as such it is
     /// fairly expensive to do. Typically it's better to just wrap your element that has a margin
@@ -371,73 +1123,1000 @@ pub fn AnimatedFor<IF, I, T, EF, N, KF, K>(
     #[prop(default = false)]
     handle_margins: bool,
 
-    /// The enter animation to use for new elements.
-    #[prop(default = FadeAnimation::default().into(), into)]
-    enter_anim: AnyEnterAnimation,
+    /// Whether move animations round their translation to whole device pixels (based on
+    /// `devicePixelRatio`). This avoids blurry text during moves on text-heavy lists. Disable it
+    /// if you need exact subpixel positioning.
+    ///
+    /// This also rounds the measured sizes to device pixels before they are compared, so that
+    /// screens with a fractional `devicePixelRatio` don't trigger size animations for subpixel
+    /// differences. To test this, zoom the page to 125% or 150%, which changes the ratio the same
+    /// way.
+    #[prop(default = true)]
+    snap_to_device_pixels: bool,
+
+    /// Whether move animations use `translate3d(x, y, 0)` instead of `translate(x, y)`. This
+    /// reliably triggers GPU compositing across browsers, which makes the moves smoother on some
+    /// engines.
+    #[prop(default = true)]
+    use_translate3d: bool,
+
+    /// Whether leaving elements get their `width` and `height` locked in place using inline
+    /// styles while they are `position:absolute`. Without this, elements that get their size from
+    /// their parent (like `width:100%` or grid items) would collapse during the leave-animation.
+    ///
+    /// Disable this for flex children, where the pinned size would override `flex-basis` and
+    /// cause a jump. In that case only `top` / `left` are set on the leaving element.
+    #[prop(default = true)]
+    lock_leave_size: bool,
+
+    /// Whether to start leave-animations in the next animation frame instead of immediately after
+    /// the element has been set to `position:absolute`. Some browsers may otherwise start the
+    /// animation from slightly stale layout values. This delays the leave by a frame.
+    #[prop(default = false)]
+    defer_leave_start: bool,
 
-    /// The leave animation to use for elements that are removed.
-    #[prop(default = FadeAnimation::default().into(), into)]
-    leave_anim: AnyLeaveAnimation,
+    /// Enable this when the `AnimatedFor` renders table rows (`<tr>`), for example as the only
+    /// child of a `<tbody>`.
+    ///
+    /// Table rows can't be set to `position:absolute` meaningfully, so instead of being taken out
+    /// of the layout, leaving rows stay in place while their cells collapse (by animating their
+    /// vertical padding, `line-height` and `font-size` to 0) using the timing of the leave
+    /// animation. The following rows slide up as the row collapses. Move animations work
+    /// normally, as rows support `transform`. Leaving rows keep their place in the `<tbody>`,
+    /// behind the row that they followed.
+    ///
+    /// Constraints: the cells must not have a fixed height and their contents should be text or
+    /// inline content that shrinks with the font size. `lock_leave_size` and `on_leave_start`'s
+    /// position are ignored for the layout in this mode.
+    #[prop(default = false)]
+    table_rows: bool,
 
-    /// The move animation to use for elements that change position.
-    #[prop(default = SlidingAnimation::default().into(), into)]
-    move_anim: AnyMoveAnimation,
-) -> impl IntoView
-where
-    IF: Fn() -> I + 'static,
-    I: IntoIterator<Item = T>,
-    EF: Fn(&T) -> N + 'static,
-    N: IntoView + 'static,
-    KF: Fn(&T) -> K + 'static,
-    K: Eq + Hash + Clone + 'static,
-    T: 'static,
-{
-    let key_fn = StoredValue::new(key);
+    /// Whether leaving elements stay in the layout instead of being set to `position:absolute`.
+    /// Following elements then only move once the leaving element is removed, unless the leave
+    /// animation shrinks it, like [`ExpandCollapseAnimation`][crate::ExpandCollapseAnimation]
+    /// does. `lock_leave_size` has no effect in this mode.
+    #[prop(default = false)]
+    keep_in_flow: bool,
 
-    let alive_items = RwSignal::new(IndexMap::<K, T>::new());
-    let leaving_items = RwSignal::new(IndexMap::<K, T>::new());
+    /// Whether to skip enter and move animations of elements that are outside of the viewport,
+    /// both before and after the change. Those elements snap to their new position instead. This
+    /// saves a lot of work for long lists where most of the items are off-screen.
+    ///
+    /// Visibility is checked using the bounding boxes of the elements at the time the animations
+    /// would start, so items that scroll into view afterwards don't animate. Leave animations are
+    /// not affected.
+    #[prop(default = false)]
+    viewport_only: bool,
 
-    let alive_items_meta = StoredValue::new(HashMap::<K, ItemMeta>::new());
+    /// Whether to animate the items when their parent element gets resized and they move without
+    /// `each` changing, for example when the items of a responsive grid wrap into a new row. This
+    /// works like calling [`AnimatedForHandle::relayout`] whenever the parent's size changes.
+    ///
+    /// The moves are started before the browser paints the resized layout, so the items never
+    /// show up at their new positions first. The resizes are debounced, so the moves are restarted
+    /// at most once per animation frame, and a resize that comes in after they have been started
+    /// in that frame is handled in the next one. A continuous resize (like dragging the window's
+    /// edge) still restarts the moves in every frame, each starting from where the items currently
+    /// are. This doesn't work well together with `animate_size`, where the animations themselves
+    /// can resize the parent.
+    #[prop(default = false)]
+    animate_on_resize: bool,
 
-    let enter_anim = StoredValue::new(enter_anim);
-    let leave_anim = StoredValue::new(leave_anim);
+    /// The maximum number of items whose enter- or move-animations are started in the same frame.
+    /// The remaining ones are started in batches in the following animation frames. Starting
+    /// hundreds of animations at once can cause the first frame to stutter, so for large lists this
+    /// trades a slightly staggered start for a smoother transition.
+    ///
+    /// While they wait, moving elements are held at their previous position using an inline
+    /// `transform` and entering elements are hidden using `visibility: hidden`. Inline values that
+    /// the elements had before are restored once their animations start.
+    #[prop(optional)]
+    batch_size: Option<usize>,
+
+    /// Function that returns a name for an item, for debugging. If set, each animation gets an
+    /// `id` like `enter:<name>`, `move:<name>` or `leave:<name>`, which shows up in the Animations
+    /// panel of the browser's DevTools and helps to see which animations run during a transition.
+    ///
+    /// Leave this unset in production to avoid the overhead.
+    #[prop(optional)]
+    anim_id: Option<Callback<K, String>>,
+
+    /// The timeline to run the enter- and move-animations on, instead of the document's default
+    /// timeline. This can be a `ScrollTimeline` or `ViewTimeline` to create scroll-driven list
+    /// animations, see [`supports_scroll_timelines`][crate::supports_scroll_timelines].
+    #[prop(optional, into)]
+    timeline: Option<web_sys::AnimationTimeline>,
+
+    /// Callback that receives a record of the animations of the most recent transition, for
+    /// debugging. It contains the snapshots and durations that were computed for each item that
+    /// got animated, which helps to find out why an element jumped. See [`TransitionRecord`].
+    ///
+    /// It's called once the enter- and move-animations have been started. Leaving items are
+    /// included as well, but without a duration if `defer_leave_start` is set.
+    #[prop(optional)]
+    debug_record: Option<Callback<Vec<TransitionRecord<K>>>>,
+
+    /// Whether to set `will-change: transform, opacity` on elements while they are animating.
+    /// This hints the browser to promote them to their own compositing layer, which can reduce
+    /// jank for large lists. The hint is removed again when the animation finishes or gets
+    /// cancelled.
+    ///
+    /// Disabled by default because overusing `will-change` costs memory.
+    #[prop(default = false)]
+    use_will_change: bool,
+
+    /// Keys of items that should not play move animations. They still take part in the layout
+    /// and can enter and leave as usual.
+    ///
+    /// This is useful for drag-and-drop reordering, where the dragged item follows the pointer
+    /// while the other items animate around it.
+    #[prop(optional, into)]
+    ignore_keys: Option<Signal<HashSet<K>>>,
+
+    /// Function that returns the element that all positions are measured against, instead of
+    /// each element's `offsetParent`. This gives consistent coordinates when the elements have
+    /// different offset parents but share a visual coordinate space.
+    ///
+    /// Leaving elements are still positioned relative to their `offsetParent`, so if they use
+    /// `position:absolute`, the reference should also be their containing block. The reference is
+    /// resolved once per change, so that the snapshots before and after use the same one.
+    ///
+    /// ```
+    /// let container = create_node_ref::<html::Div>();
+    /// let reference = Callback::new(move |_| {
+    ///     container.get_untracked().map(|el| web_sys::Element::from((*el).clone()))
+    /// });
+    /// ```
+    #[prop(optional)]
+    reference: Option<Callback<(), Option<web_sys::Element>>>,
+
+    /// How the positions of the items are measured, see [`PositionSource`]. With a `reference`,
+    /// the positions are always measured using bounding rects.
+    #[prop(optional)]
+    position_source: PositionSource,
+
+    /// Whether to compensate for a container that is scaled using a transform, for example a
+    /// zoomable canvas. Sizes and bounding rects are measured on screen, while the animations
+    /// are applied in the element's own (unscaled) space, so moves and size changes would be off
+    /// by the scale factor otherwise. The scale is detected by comparing the container's size on
+    /// screen with its layout size; rotations and skews are not supported.
+    #[prop(default = true)]
+    compensate_scale: bool,
+
+    /// Whether to temporarily set `content-visibility: visible` on the items while their
+    /// snapshots are taken. Items with `content-visibility: auto` that are off-screen skip the
+    /// layout of their contents and are measured with their `contain-intrinsic-size` instead of
+    /// their real size, so they would animate to and from the wrong sizes.
+    ///
+    /// This forces the layout of the contents of all items twice per change (once for each
+    /// snapshot), which costs part of what `content-visibility` saves. It's not needed if the
+    /// `contain-intrinsic-size` of the items matches their real size and `animate_size` is off.
+    #[prop(default = false)]
+    force_content_visibility: bool,
+
+    /// How to animate when the list gets replaced, that is when none of the previous items remain
+    /// in it. Having all the old items leave while all the new ones enter can look chaotic, so
+    /// [`ReplaceMode::Sequential`] lets the new ones wait for the old ones instead. Lists that are
+    /// cleared or that were empty before are not affected.
+    #[prop(optional)]
+    replace_mode: ReplaceMode,
+
+    /// Function that picks the enter-animation depending on why the item entered, for example to
+    /// play a different animation for items that were re-added while leaving than for new ones.
+    /// Falls back to `enter_anim` if it returns `None`.
+    ///
+    /// It's called for every entering item, so it should be cheap.
+    #[prop(optional)]
+    enter_anim_by_reason: Option<Callback<EnterReason, Option<AnyEnterAnimation>>>,
+
+    /// Like `enter_anim_by_reason`, but also gets the position of the item in the list, so that
+    /// the enter-animation can depend on where the item is. It takes precedence over
+    /// `enter_anim_by_reason`, which is used if it returns `None`.
+    ///
+    /// ```
+    /// // Notifications at the top of the stack come in from above, the others from below.
+    /// let enter_anim_by_context = Callback::new(|context: EnterContext| {
+    ///     let y = if context.is_first() { -40.0 } else { 40.0 };
+    ///     let offset = Position { x: 0.0, y };
+    ///     Some(SlideAnimation::new(Duration::from_millis(200), "ease-out", offset).into())
+    /// });
+    /// ```
+    #[prop(optional)]
+    enter_anim_by_context: Option<Callback<EnterContext, Option<AnyEnterAnimation>>>,
+
+    /// Like `enter_anim_by_reason`, but for the leave-animation. Falls back to `leave_anim` if it
+    /// returns `None`.
+    #[prop(optional)]
+    leave_anim_by_reason: Option<Callback<LeaveReason, Option<AnyLeaveAnimation>>>,
+
+    /// Picks the leave-animation of an item by its key, for lists whose items need different
+    /// animations. Takes precedence over `leave_anim_by_reason`, which is used if it returns
+    /// `None`.
+    #[prop(optional)]
+    leave_anim_by_key: Option<Callback<K, Option<AnyLeaveAnimation>>>,
+
+    /// Picks the move-animation of an item by its key. Takes precedence over `reorder_move_anim`
+    /// and `move_anim`, which are used if it returns `None`. Also used when the item slides in
+    /// with `slide_enter`.
+    #[prop(optional)]
+    move_anim_by_key: Option<Callback<K, Option<AnyMoveAnimation>>>,
+
+    /// Function that decides whether an element should play a move-animation, given its snapshots
+    /// from before and after the change. Use this for example to only animate vertical moves or
+    /// to ignore moves below a threshold. By default, elements move if their position or size
+    /// changed at all.
+    ///
+    /// ```
+    /// let should_move = Callback::new(|(from, to): (ElementSnapshot, ElementSnapshot)| {
+    ///     (from.position().y - to.position().y).abs() > 2.0
+    /// });
+    /// ```
+    #[prop(optional)]
+    should_move: Option<Callback<(ElementSnapshot, ElementSnapshot), bool>>,
+
+    /// Handle that allows triggering animations from the outside, see [`AnimatedForHandle`].
+    #[prop(optional)]
+    handle: Option<AnimatedForHandle<K>>,
+
+    /// The enter animation to use for new elements.
+    #[prop(default = FadeAnimation::default().into(), into)]
+    enter_anim: AnyEnterAnimation,
+
+    /// The leave animation to use for elements that are removed.
+    #[prop(default = FadeAnimation::default().into(), into)]
+    leave_anim: AnyLeaveAnimation,
+
+    /// The move animation to use for elements that change position.
+    #[prop(default = SlidingAnimation::default().into(), into)]
+    move_anim: AnyMoveAnimation,
+
+    /// The move animation to use instead of `move_anim` when the list got reordered, for example
+    /// to have sorting use a springier animation than the moves that make room for new items.
+    ///
+    /// A change counts as a reorder if the items that are in the list both before and after it
+    /// are in a different order. All moves of that change use this animation, including those
+    /// of items that only move because others entered or left in the same change. Moves of
+    /// changes that keep the order (items only entered or left) and those started by
+    /// [`AnimatedForHandle::relayout`] use `move_anim`.
+    #[prop(optional, into)]
+    reorder_move_anim: Option<AnyMoveAnimation>,
+
+    /// Animation for the parent element of the items when the list becomes empty. It collapses
+    /// the parent from its previous size to its size without the items, starting together with
+    /// the leave-animations of the last items. The parent should use `overflow:hidden` (or
+    /// `clip`) so that the leaving items don't stick out of it.
+    ///
+    /// Only items that are taken out of the layout while leaving free up space, so this has no
+    /// effect with `keep_in_flow` or `table_rows`.
+    #[prop(optional, into)]
+    collapse_anim: Option<AnyResizeAnimation>,
+
+    /// CSS containment to set on the parent element of the items while a transition is running,
+    /// for example `layout paint`. It keeps the browser from re-laying out the rest of the page
+    /// on every frame, which can cut the cost of transitions with hundreds of animating items.
+    /// The inline style is removed again once all animations inside the parent have finished.
+    ///
+    /// Containment changes how the parent renders:
+    /// - `layout` and `paint` make it the containing block of its positioned descendants, so
+    ///   leaving items would jump if it isn't positioned (for example `position:relative`)
+    ///   already.
+    /// - `paint` clips everything that overflows it, like `overflow:clip`.
+    /// - `size` ignores the children when sizing the parent, so it collapses unless it has an
+    ///   explicit size. Avoid it here.
+    #[prop(optional, into)]
+    transition_contain: Option<Oco<'static, str>>,
+) -> impl IntoView
+where
+    IF: Fn() -> I + 'static,
+    I: IntoIterator<Item = T>,
+    EF: Fn(&T) -> N + 'static,
+    N: IntoView + 'static,
+    KF: Fn(&T) -> K + 'static,
+    K: Eq + Hash + Clone + 'static,
+    T: 'static,
+{
+    let key_fn = StoredValue::new(key);
+
+    let alive_items = RwSignal::new(IndexMap::<K, T>::new());
+    let leaving_items = RwSignal::new(IndexMap::<K, T>::new());
+
+    let alive_items_meta = StoredValue::new(HashMap::<K, ItemMeta>::new());
+
+    // The keys in the order they were last rendered in, and for each leaving item the alive item
+    // it was rendered after (if any). Leaving items stay behind that item so that they don't jump
+    // to the end and the `<For>` doesn't have to move their DOM nodes.
+    let rendered_keys = StoredValue::new(Vec::<K>::new());
+    let leaving_anchors = StoredValue::new(IndexMap::<K, Option<K>>::new());
+
+    // The leave-animations that are currently running, so they can be finished early.
+    let leaving_anims = StoredValue::new(HashMap::<K, Animation>::new());
+
+    // The safety-net timeouts of the leave-animations, see `start_leave`.
+    let leave_timeouts = StoredValue::new(HashMap::<K, TimeoutHandle>::new());
+
+    // Browsers throttle or pause animations in background tabs, so leaving elements could pile up
+    // until the user returns. Fast-forward them instead so the DOM is consistent.
+    let visibility_listener = window_event_listener(ev::visibilitychange, move |_| {
+        if !document().hidden() {
+            return;
+        }
+        leaving_anims.with_value(|leaving_anims| {
+            for anim in leaving_anims.values() {
+                _ = anim.finish();
+            }
+        });
+    });
+    on_cleanup(move || visibility_listener.remove());
+
+    let enter_anim = StoredValue::new(enter_anim);
+    let leave_anim = StoredValue::new(leave_anim);
     let move_anim = StoredValue::new(move_anim);
+    let collapse_anim = StoredValue::new(collapse_anim);
+    let reorder_move_anim = StoredValue::new(reorder_move_anim);
+    let transition_contain = StoredValue::new(transition_contain);
+
+    // Incremented on each transition, so that only the last one removes `transition_contain`.
+    let transition_id = StoredValue::new(0usize);
+
+    // See `debug_record`.
+    let records = StoredValue::new(Vec::<TransitionRecord<K>>::new());
+
+    let record = move |record: TransitionRecord<K>| {
+        if debug_record.is_some() {
+            records.update_value(|records| records.push(record));
+        }
+    };
+
+    let record_job = move |k: &K, job: AnimJob, anim: Option<&Animation>| {
+        let (kind, from, to) = match job {
+            AnimJob::Enter { slide_from, to, .. } => (TransitionKind::Enter, slide_from, to),
+            AnimJob::Move { from, to, .. } => (TransitionKind::Move, Some(from), to),
+        };
+
+        record(TransitionRecord {
+            key: k.clone(),
+            kind,
+            from,
+            to: Some(to),
+            duration: anim.and_then(anim_duration),
+        });
+    };
+
+    // Names the animation for the browser's DevTools, see `anim_id`.
+    let set_anim_id = move |anim: &Animation, phase: &str, k: &K| {
+        if let Some(anim_id) = anim_id {
+            anim.set_id(&format!("{phase}:{}", anim_id(k.clone())));
+        }
+    };
+
+    // Removes a leaving item once its leave-animation `anim` is over. Items can be re-added while
+    // they are leaving and then leave again, so callbacks of an earlier leave-animation must not
+    // remove the item early. Returns whether the item was removed.
+    let remove_leaving = move |k: &K, anim: &Animation| {
+        let is_current = leaving_anims
+            .try_update_value(|leaving_anims| {
+                if leaving_anims.get(k) == Some(anim) {
+                    leaving_anims.remove(k);
+                    true
+                } else {
+                    false
+                }
+            })
+            .unwrap_or(false);
+
+        if is_current {
+            leaving_items.try_update(|leaving_items| {
+                leaving_items.swap_remove(k);
+            });
+        }
+
+        is_current
+    };
+
+    // The leave-animation of an item, see `leave_anim_by_key` and `leave_anim_by_reason`.
+    let pick_leave_anim = move |k: &K, reason: LeaveReason| {
+        leave_anim_by_key
+            .and_then(|f| f(k.clone()))
+            .or_else(|| leave_anim_by_reason.and_then(|f| f(reason)))
+            .unwrap_or_else(|| leave_anim.get_value())
+    };
+
+    // Starts the leave-animation on an element that has already been taken out of the layout, and
+    // removes the item once it has finished.
+    let start_leave = move |k: K, el: web_sys::HtmlElement, leave: AnyLeaveAnimation| {
+        if use_will_change {
+            set_will_change(&el);
+        }
+
+        let (anim, options) = leave.anim.animate(&el);
+        set_anim_id(&anim, "leave", &k);
+
+        if table_rows {
+            collapse_table_row(&el, &options);
+        }
+
+        // Safety net in case `onfinish` never fires, for example because the animation got cancelled
+        // externally. Otherwise the element would never be removed.
+        let timeout = set_timeout_with_handle(
+            {
+                let k = k.clone();
+                let anim = anim.clone();
+                move || {
+                    remove_leaving(&k, &anim);
+                }
+            },
+            options.delay + options.duration + LEAVE_TIMEOUT_BUFFER,
+        )
+        .ok();
+
+        if let Some(timeout) = timeout {
+            leave_timeouts.update_value(|leave_timeouts| {
+                // The item left again after being re-added, so its previous leave is over.
+                if let Some(prev) = leave_timeouts.insert(k.clone(), timeout) {
+                    prev.clear();
+                }
+            });
+        }
+
+        // Remove leaving elements after their exit-animation
+        let closure = Closure::<dyn Fn(web_sys::Event)>::new({
+            let k = k.clone();
+            let el = el.clone();
+            let anim = anim.clone();
+            move |_| {
+                if let Some(timeout) = timeout {
+                    timeout.clear();
+                    leave_timeouts.try_update_value(|leave_timeouts| {
+                        if leave_timeouts.get(&k) == Some(&timeout) {
+                            leave_timeouts.remove(&k);
+                        }
+                    });
+                }
+                if use_will_change {
+                    clear_will_change(&el);
+                }
+
+                if remove_leaving(&k, &anim) {
+                    if let Some(on_leave_end) = on_leave_end {
+                        on_leave_end(el.clone().into());
+                    }
+                }
+            }
+        })
+        .into_js_value();
+
+        anim.set_onfinish(Some(&closure.into()));
+
+        leaving_anims.update_value(|leaving_anims| {
+            leaving_anims.insert(k, anim);
+        });
+
+        options.duration
+    };
+
+    let reference_el = StoredValue::new(None::<web_sys::Element>);
+    let resolve_reference = move || {
+        reference_el.set_value(reference.and_then(|reference| reference(())));
+    };
+
+    // The parent element of the items, found through the first item that has an element.
+    let items_parent = move || {
+        use wasm_bindgen::JsCast;
+
+        alive_items_meta.with_value(|alive_items_meta| {
+            alive_items_meta.values().find_map(|meta| {
+                let parent = meta.el.as_ref()?.parent_element()?;
+                parent.dyn_into::<web_sys::HtmlElement>().ok()
+            })
+        })
+    };
+
+    let needs_move = move |from: ElementSnapshot, to: ElementSnapshot| match should_move {
+        Some(should_move) => should_move((from, to)),
+        None => from != to,
+    };
+
+    let take_snapshot = move |el: &web_sys::HtmlElement| {
+        let device_pixel_ratio = snap_to_device_pixels.then(|| window().device_pixel_ratio());
+        reference_el.with_value(|reference| {
+            let offset_parent = (reference.is_none()
+                && position_source == PositionSource::BoundingRect)
+                .then(|| el.offset_parent())
+                .flatten();
+
+            get_el_snapshot(
+                el,
+                animate_size,
+                handle_margins,
+                device_pixel_ratio,
+                reference.as_ref().or(offset_parent.as_ref()),
+                compensate_scale,
+            )
+        })
+    };
+
+    // How far an element is from its layout box because of its running animations, so that
+    // items that are interrupted mid-move continue from where they are instead of jumping back to
+    // their layout box first. With a `reference` or `PositionSource::BoundingRect`, and for SVG
+    // elements (which are measured relative to their `<svg>`), the snapshots include the
+    // transforms already.
+    let in_flight_offset = move |el: &web_sys::HtmlElement, anims: &[Animation]| {
+        if position_source == PositionSource::BoundingRect
+            || reference_el.with_value(Option::is_some)
+            || is_svg(el)
+        {
+            return None;
+        }
+        in_flight_translation(el, anims)
+    };
+
+    let move_settings = move || MoveAnimSettings {
+        animate_size,
+        device_pixel_ratio: snap_to_device_pixels.then(|| window().device_pixel_ratio()),
+        use_translate3d,
+        scale_size,
+        clip_on_shrink,
+    };
+
+    let timeline = StoredValue::new(timeline);
+
+    // Snapshots of all items that have an element. With `force_content_visibility`, their
+    // contents are made visible for all of them at once, so that the layout is only forced once.
+    let take_snapshots = move |items: &HashMap<K, ItemMeta>| {
+        let els = items
+            .iter()
+            .filter_map(|(k, meta)| Some((k, meta.el.as_ref()?)))
+            .collect::<Vec<_>>();
+
+        let prev_content_visibility = force_content_visibility.then(|| {
+            els.iter()
+                .map(|(_, el)| {
+                    let style = el.style();
+                    let prev = style.get_property_value("content-visibility").unwrap();
+                    style.set_property("content-visibility", "visible").unwrap();
+                    prev
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let snapshots = els
+            .iter()
+            .map(|(k, el)| ((*k).clone(), take_snapshot(el)))
+            .collect::<HashMap<_, _>>();
+
+        if let Some(prev_content_visibility) = prev_content_visibility {
+            for ((_, el), prev) in els.iter().zip(prev_content_visibility) {
+                let style = el.style();
+                if prev.is_empty() {
+                    style.remove_property("content-visibility").unwrap();
+                } else {
+                    style.set_property("content-visibility", &prev).unwrap();
+                }
+            }
+        }
+
+        snapshots
+    };
+
+    // Starts the animations of an item, returning them so that they can be cancelled later.
+    let start_anim_job = move |k: &K, el: &web_sys::HtmlElement, job: AnimJob| {
+        let move_settings = move_settings();
+
+        let anims = match job {
+            AnimJob::Enter {
+                slide_from,
+                to,
+                context,
+            } => {
+                if let Some(on_enter_start) = on_enter_start {
+                    on_enter_start(el.clone().into());
+                }
+
+                if use_will_change {
+                    set_will_change(el);
+                }
+
+                let anim = match enter_anim_by_context
+                    .and_then(|f| f(context))
+                    .or_else(|| enter_anim_by_reason.and_then(|f| f(context.reason)))
+                {
+                    Some(enter_anim) => enter_anim.anim.animate(el),
+                    None => enter_anim.with_value(|enter_anim| enter_anim.anim.animate(el)),
+                };
+                set_anim_id(&anim, "enter", k);
+
+                if use_will_change {
+                    restore_styles_on_end(el, &anim, clear_will_change);
+                }
+
+                if let Some(on_enter_end) = on_enter_end {
+                    let closure = Closure::<dyn Fn(web_sys::Event)>::new({
+                        let el = el.clone();
+                        move |_| on_enter_end(el.clone().into())
+                    })
+                    .into_js_value();
+
+                    anim.set_onfinish(Some(&closure.into()));
+                }
+
+                let mut anims = vec![anim];
+
+                // Slide in along with the neighbors that make room for this item.
+                if let Some(from) = slide_from {
+                    let anim = match move_anim_by_key.and_then(|f| f(k.clone())) {
+                        Some(move_anim) => move_anim.anim.animate(el, from, to, move_settings),
+                        None => move_anim.with_value(|move_anim| {
+                            move_anim.anim.animate(el, from, to, move_settings)
+                        }),
+                    };
+                    set_anim_id(&anim, "slide-enter", k);
+                    if use_will_change {
+                        restore_styles_on_end(el, &anim, clear_will_change);
+                    }
+                    anims.push(anim);
+                }
+
+                anims
+            }
+            AnimJob::Move { from, to, reorder } => {
+                if use_will_change {
+                    set_will_change(el);
+                }
+
+                let anim = move_anim_by_key
+                    .and_then(|f| f(k.clone()))
+                    .map(|move_anim| move_anim.anim.animate(el, from, to, move_settings))
+                    .or_else(|| {
+                        reorder_move_anim.with_value(|reorder_move_anim| {
+                            let reorder_move_anim =
+                                reorder_move_anim.as_ref().filter(|_| reorder)?;
+                            Some(reorder_move_anim.anim.animate(el, from, to, move_settings))
+                        })
+                    })
+                    .unwrap_or_else(|| {
+                        move_anim.with_value(|move_anim| {
+                            move_anim.anim.animate(el, from, to, move_settings)
+                        })
+                    });
+                set_anim_id(&anim, "move", k);
+
+                if use_will_change {
+                    restore_styles_on_end(el, &anim, clear_will_change);
+                }
+
+                #[cfg(debug_assertions)]
+                warn_if_off_goal(&anim, el, to, take_snapshot);
+
+                vec![anim]
+            }
+        };
+
+        if let Some(timeline) = timeline.get_value() {
+            for anim in &anims {
+                anim.set_timeline(Some(&timeline));
+            }
+        }
+
+        anims
+    };
+
+    // Starts the animations that got deferred because of `batch_size`. Items whose job has been
+    // superseded by a newer change in the meantime are skipped.
+    let start_deferred_jobs = move |keys: Vec<K>| {
+        alive_items_meta.try_update_value(|items| {
+            for k in keys {
+                let Some(meta) = items.get_mut(&k) else {
+                    continue;
+                };
+                let (Some(el), Some(hold)) = (meta.el.clone(), meta.pending_job.take()) else {
+                    continue;
+                };
+
+                let job = release_anim_hold(&el, hold);
+                meta.cur_anims.extend(start_anim_job(&k, &el, job));
+            }
+        });
+    };
+
+    // Animate the items from their last goal snapshots to their current positions, see
+    // `AnimatedForHandle::relayout`.
+    let relayout = move || {
+        if is_server() {
+            return;
+        }
+
+        queue_microtask(move || {
+            // The component may have been unmounted in the meantime.
+            let Some(keys) = alive_items
+                .try_with_untracked(|alive_items| alive_items.keys().cloned().collect::<Vec<_>>())
+            else {
+                return;
+            };
+
+            alive_items_meta.try_update_value(|items| {
+                for meta in items.values_mut() {
+                    meta.refresh_el();
+                }
+
+                // Take all snapshots first, see the move phase in the effect below.
+                let new_snapshots = take_snapshots(items);
+
+                for k in &keys {
+                    let Some(meta) = items.get_mut(k) else {
+                        continue;
+                    };
+                    let (Some(el), Some(&new_snapshot)) = (meta.el.clone(), new_snapshots.get(k))
+                    else {
+                        continue;
+                    };
+
+                    let Some(prev_snapshot) = meta.snapshot.replace(new_snapshot) else {
+                        continue;
+                    };
+
+                    let ignored = ignore_keys.is_some_and(|ignore_keys| {
+                        ignore_keys.with_untracked(|ignore_keys| ignore_keys.contains(k))
+                    });
+
+                    let prev_snapshot = match in_flight_offset(&el, &meta.cur_anims) {
+                        Some(offset) => ElementSnapshot {
+                            position: prev_snapshot.position + offset,
+                            ..prev_snapshot
+                        },
+                        None => prev_snapshot,
+                    };
+
+                    if ignored || !needs_move(prev_snapshot, new_snapshot) {
+                        continue;
+                    }
+
+                    for cur_anim in meta.cur_anims.drain(..) {
+                        cur_anim.cancel();
+                    }
+
+                    if let Some(hold) = meta.pending_job.take() {
+                        release_anim_hold(&el, hold);
+                    }
+
+                    let job = AnimJob::Move {
+                        from: prev_snapshot,
+                        to: new_snapshot,
+                        reorder: false,
+                    };
+
+                    meta.cur_anims.extend(start_anim_job(k, &el, job));
+                }
+            });
+        });
+    };
+
+    let resize_observer = StoredValue::new(None::<web_sys::ResizeObserver>);
+
+    // `Some` while `relayout` has already run for a resize in the current frame, `Some(true)` if
+    // the parent got resized again since.
+    let resized_in_frame = StoredValue::new(None::<bool>);
+
+    // Runs `relayout` for `animate_on_resize`, at most once per animation frame.
+    let on_resize = move || {
+        if resized_in_frame.get_value().is_some() {
+            resized_in_frame.set_value(Some(true));
+            return;
+        }
+
+        resized_in_frame.set_value(Some(false));
+        relayout();
+
+        request_animation_frame(move || {
+            if resized_in_frame.try_update_value(Option::take) == Some(Some(true)) {
+                relayout();
+            }
+        });
+    };
+
+    // Starts observing the parent of the items for `animate_on_resize`, once it is known.
+    let observe_resize = move || {
+        use wasm_bindgen::JsCast;
+
+        if !animate_on_resize || resize_observer.with_value(Option::is_some) {
+            return;
+        }
+        let Some(parent) = items_parent() else {
+            return;
+        };
+
+        let callback = Closure::<dyn Fn()>::new(on_resize).into_js_value();
+        let observer = web_sys::ResizeObserver::new(callback.unchecked_ref()).unwrap();
+        observer.observe(&parent);
+        resize_observer.set_value(Some(observer));
+    };
+
+    // Stop everything that is still running when the component is unmounted, so that nothing
+    // fires after it is gone. Cancelled animations don't call their `onfinish` handlers.
+    on_cleanup(move || {
+        leave_timeouts.try_update_value(|leave_timeouts| {
+            for (_, timeout) in leave_timeouts.drain() {
+                timeout.clear();
+            }
+        });
+        leaving_anims.try_with_value(|leaving_anims| {
+            for anim in leaving_anims.values() {
+                anim.cancel();
+            }
+        });
+        alive_items_meta.try_with_value(|items| {
+            for anim in items.values().flat_map(|meta| &meta.cur_anims) {
+                anim.cancel();
+            }
+        });
+    });
+
+    on_cleanup(move || {
+        resize_observer.try_with_value(|observer| {
+            if let Some(observer) = observer {
+                observer.disconnect();
+            }
+        });
+    });
+
+    if let Some(handle) = handle {
+        handle
+            .relayout
+            .set_value(Some(Callback::new(move |_| relayout())));
+        handle
+            .current_animation
+            .set_value(Some(Callback::new(move |k: K| {
+                alive_items_meta
+                    .try_with_value(|items| items.get(&k)?.cur_anims.last().cloned())
+                    .flatten()
+                    .or_else(|| {
+                        leaving_anims
+                            .try_with_value(|leaving_anims| leaving_anims.get(&k).cloned())
+                            .flatten()
+                    })
+            })));
+        on_cleanup(move || {
+            handle.relayout.try_set_value(None);
+            handle.current_animation.try_set_value(None);
+        });
+
+        create_isomorphic_effect(move |_| {
+            let keys = leaving_items.with(|leaving_items| leaving_items.keys().cloned().collect());
+            handle.leaving_keys.set(keys);
+        });
+    }
 
     // Listen to changes in `each`. This handles all the animations.
     create_isomorphic_effect(move |prev| {
+        if debug_record.is_some() {
+            records.set_value(Vec::new());
+        }
+
         let new_items = each()
             .into_iter()
             .map(|i| (key_fn.with_value(|k| k(&i)), i))
             .collect::<IndexMap<_, _>>();
 
+        if !is_server() {
+            resolve_reference();
+        }
+
+        // The containment must already apply to the first snapshots, since it can change the
+        // offset parent of the items. It is only removed once the goal snapshots have been taken
+        // and the animations have finished.
+        let contained_parent = transition_contain.with_value(|contain| {
+            let contain = contain
+                .as_ref()
+                .filter(|_| !is_server() && prev.is_some())?;
+            let parent = items_parent()?;
+            parent.style().set_property("contain", contain).unwrap();
+            Some(parent)
+        });
+
+        // The children may have re-rendered since the last change, so the elements that are
+        // measured, moved or taken out of the layout must be the current ones.
+        if !is_server() {
+            alive_items_meta.update_value(|items| {
+                // Items whose element hasn't been found at all are handled by `unresolved` below.
+                for meta in items.values_mut() {
+                    if meta.el.is_some() {
+                        meta.refresh_el();
+                    }
+                }
+            });
+        }
+
         // Get initial snapshots of all previously alive elements
         let snapshots = alive_items_meta.with_value(|alive_items_meta| {
-            alive_items_meta
-                .iter()
-                .map(|(k, meta)| {
-                    (k.clone(), {
-                        if is_server() {
-                            ElementSnapshot::default()
-                        } else {
-                            get_el_snapshot(
-                                &meta.el.as_ref().expect("el always exists on the client"),
-                                animate_size,
-                                handle_margins,
-                            )
-                        }
+            if is_server() {
+                alive_items_meta
+                    .keys()
+                    .map(|k| (k.clone(), ElementSnapshot::default()))
+                    .collect::<HashMap<_, _>>()
+            } else {
+                take_snapshots(alive_items_meta)
+            }
+        });
+
+        // The snapshots are of the layout boxes, which don't include the transforms of running
+        // animations, see `in_flight_offset`.
+        let in_flight = if is_server() {
+            HashMap::new()
+        } else {
+            alive_items_meta.with_value(|alive_items_meta| {
+                alive_items_meta
+                    .iter()
+                    .filter_map(|(k, meta)| {
+                        let offset = in_flight_offset(meta.el.as_ref()?, &meta.cur_anims)?;
+                        Some((k.clone(), offset))
                     })
-                })
-                .collect::<HashMap<_, _>>()
+                    .collect::<HashMap<_, _>>()
+            })
+        };
+
+        // Items whose element hasn't been found yet, see `find_first_element`. If it is only found
+        // after this change, the item was already present before and must not enter.
+        let unresolved = if is_server() {
+            HashSet::new()
+        } else {
+            alive_items_meta.with_value(|alive_items_meta| {
+                alive_items_meta
+                    .iter()
+                    .filter(|(_, meta)| meta.el.is_none())
+                    .map(|(k, _)| k.clone())
+                    .collect::<HashSet<_>>()
+            })
+        };
+
+        // For `collapse_anim`, the parent and its size if this change empties the list.
+        let collapse = (!is_server() && new_items.is_empty())
+            .then(|| {
+                let parent = items_parent()?;
+                let anim = collapse_anim.get_value()?;
+                let from = border_box_extent(&parent);
+                Some((anim, parent, from))
+            })
+            .flatten();
+
+        transition_id.update_value(|id| *id += 1);
+        let id = transition_id.get_value();
+
+        // Whether the items that stay have changed their order, see `reorder_move_anim`.
+        let reordered = alive_items.with_untracked(|alive_items| {
+            let prev_order = alive_items.keys().filter(|k| new_items.contains_key(*k));
+            let new_order = new_items.keys().filter(|k| alive_items.contains_key(*k));
+            !prev_order.eq(new_order)
         });
 
+        // For `ReplaceMode::Sequential`, the keys of the previous items if none of them remain.
+        let replaced_keys = (replace_mode == ReplaceMode::Sequential && !new_items.is_empty())
+            .then(|| {
+                alive_items.with_untracked(|alive_items| {
+                    (!alive_items.is_empty()
+                        && !alive_items.keys().any(|k| new_items.contains_key(k)))
+                    .then(|| alive_items.keys().cloned().collect::<Vec<_>>())
+                })
+            })
+            .flatten();
+
         // Items that are re-added during the animation while they are still leaving must be
         // removed from the leaving_items list and will then be treated as new elements (Their
-        // scope already got disposed, so there's no way to resurrect them).
+        // scope already got disposed, so there's no way to resurrect them). The new element
+        // starts out with its own inline styles, so the ones that the leave changed on the old
+        // element don't need to be restored.
+        let mut resurrected = HashSet::new();
         for k in new_items.keys() {
             if leaving_items.with_untracked(|leaving_items| leaving_items.contains_key(k)) {
+                resurrected.insert(k.clone());
                 leaving_items.update(|leaving_items| {
                     leaving_items.swap_remove(k);
                 });
+
+                // The old element is removed right away, so its animation doesn't need to finish.
+                leaving_anims.update_value(|leaving_anims| {
+                    if let Some(anim) = leaving_anims.remove(k) {
+                        anim.cancel();
+                    }
+                });
             }
         }
 
@@ -451,17 +2130,28 @@ where
             let snapshots = &snapshots;
             move || {
                 alive_items.update(move |alive_items| {
-                    let items_to_remove = alive_items
+                    let leave_reason = if alive_items.keys().any(|k| new_items.contains_key(k)) {
+                        LeaveReason::Remove
+                    } else {
+                        LeaveReason::RemoveAll
+                    };
+
+                    let mut items_to_remove = alive_items
                         .drain(..)
                         .filter(|(k, _)| !new_items.contains_key(k))
                         .collect::<Vec<_>>();
 
+                    // Items without an element can't be animated and are removed immediately.
+                    let mut removed_immediately = HashSet::new();
+
                     alive_items_meta.update_value(|alive_items_meta| {
                         for (k, _) in items_to_remove.iter() {
                             let Some(ItemMeta {
                                 el,
                                 scope,
-                                cur_anim,
+                                cur_anims,
+                                pending_job,
+                                ..
                             }) = alive_items_meta.remove(k)
                             else {
                                 continue;
@@ -473,12 +2163,19 @@ where
                                 return;
                             }
 
-                            let el = el.expect("el always exists on the client");
+                            let Some(el) = el else {
+                                removed_immediately.insert(k.clone());
+                                continue;
+                            };
 
                             let snapshot = snapshots.get(k).unwrap();
 
+                            if let Some(hold) = pending_job {
+                                release_anim_hold(&el, hold);
+                            }
+
                             if let Some(on_leave_start) = on_leave_start {
-                                on_leave_start((el.clone(), snapshot.position));
+                                on_leave_start((el.clone().into(), snapshot.position));
                             }
 
                             let extent = if animate_size {
@@ -490,44 +2187,87 @@ where
                                 }
                             };
 
-                            if let Some(cur_anim) = cur_anim {
-                                cur_anim.cancel();
-                            }
+                            // Leave-animations with `from_current` continue from the state of
+                            // the interrupted animations.
+                            let leave = pick_leave_anim(k, leave_reason);
+                            cancel_keeping_values(
+                                &el,
+                                cur_anims,
+                                &leave.anim.from_current_properties(),
+                            );
+
+                            // Table rows can't be taken out of the layout using
+                            // `position:absolute`, so they stay in place and collapse instead.
+                            if !table_rows && !keep_in_flow {
+                                let style = el.style();
+                                style.set_property("position", "absolute").unwrap();
+                                style
+                                    .set_property("top", &format!("{}px", snapshot.position.y))
+                                    .unwrap();
+                                style
+                                    .set_property("left", &format!("{}px", snapshot.position.x))
+                                    .unwrap();
+
+                                if lock_leave_size {
+                                    style
+                                        .set_property("width", &format!("{}px", extent.width))
+                                        .unwrap();
+
+                                    style
+                                        .set_property("height", &format!("{}px", extent.height))
+                                        .unwrap();
+                                }
+                            }
+
+                            // Force a reflow so that the new positioning is applied before
+                            // the animation starts.
+                            _ = el.offset_height();
+
+                            let duration = if defer_leave_start {
+                                request_animation_frame({
+                                    let k = k.clone();
+                                    move || {
+                                        // The item may have been re-added in the meantime.
+                                        let still_leaving = leaving_items
+                                            .try_with_untracked(|leaving_items| {
+                                                leaving_items.contains_key(&k)
+                                            })
+                                            .unwrap_or(false);
+
+                                        if still_leaving {
+                                            start_leave(k, el, leave);
+                                        }
+                                    }
+                                });
+                                None
+                            } else {
+                                Some(start_leave(k.clone(), el, leave))
+                            };
+
+                            record(TransitionRecord {
+                                key: k.clone(),
+                                kind: TransitionKind::Leave,
+                                from: Some(*snapshot),
+                                to: None,
+                                duration,
+                            });
+                        }
+                    });
 
-                            let style = el.style();
-                            style.set_property("position", "absolute").unwrap();
-                            style
-                                .set_property("top", &format!("{}px", snapshot.position.y))
-                                .unwrap();
-                            style
-                                .set_property("left", &format!("{}px", snapshot.position.x))
-                                .unwrap();
-
-                            style
-                                .set_property("width", &format!("{}px", extent.width))
-                                .unwrap();
-
-                            style
-                                .set_property("height", &format!("{}px", extent.height))
-                                .unwrap();
-
-                            let anim =
-                                leave_anim.with_value(|leave_anim| leave_anim.anim.animate(&el));
-
-                            // Remove leaving elements after their exit-animation
-                            let closure = Closure::<dyn Fn(web_sys::Event)>::new({
-                                let k = k.clone();
-                                move |_| {
-                                    leaving_items.try_update(|leaving_items| {
-                                        leaving_items.swap_remove(&k);
-                                    });
-                                }
-                            })
-                            .into_js_value();
+                    items_to_remove.retain(|(k, _)| !removed_immediately.contains(k));
 
-                            anim.set_onfinish(Some(&closure.into()));
+                    leaving_anchors.set_value(rendered_keys.with_value(|rendered_keys| {
+                        let mut anchor = None;
+                        let mut anchors = IndexMap::new();
+                        for k in rendered_keys {
+                            if new_items.contains_key(k) {
+                                anchor = Some(k.clone());
+                            } else {
+                                anchors.insert(k.clone(), anchor.clone());
+                            }
                         }
-                    });
+                        anchors
+                    }));
 
                     leaving_items.update(move |leaving_items| {
                         leaving_items.extend(items_to_remove);
@@ -537,63 +2277,413 @@ where
             }
         });
 
+        // The leaving items have been taken out of the layout by now, so the parent already has
+        // its collapsed size.
+        if let Some((anim, parent, from)) = collapse {
+            let collapse = move || {
+                let to = border_box_extent(&parent);
+                if from.width != to.width || from.height != to.height {
+                    anim.animate(&parent, from, to);
+                }
+            };
+
+            // Start together with the leave-animations, see `defer_leave_start`.
+            if defer_leave_start {
+                request_animation_frame(collapse);
+            } else {
+                collapse();
+            }
+        }
+
         // Wait for the children to be created so that we get element refs for enter-animation
         queue_microtask(move || {
             if is_server() {
                 return;
             }
+
+            // On the first run, the reference may not have been mounted yet.
+            if prev.is_none() {
+                resolve_reference();
+            }
+
+            if let Some(on_after_dom_update) = on_after_dom_update {
+                on_after_dom_update(());
+            }
+
+            observe_resize();
+
             if prev.is_none() && !appear {
+                alive_items_meta.update_value(|items| {
+                    for meta in items.values_mut() {
+                        // Elements that are rendered lazily need to be found now, so that the
+                        // next change has their snapshot and doesn't treat them as entering.
+                        meta.refresh_el();
+
+                        // `relayout` needs to know where the elements started.
+                        if handle.is_some() || animate_on_resize {
+                            meta.snapshot = meta.el.as_ref().map(take_snapshot);
+                        }
+                    }
+                });
                 return;
             }
+
             alive_items_meta.update_value(|items| {
-                for (k, meta) in items.iter_mut() {
-                    let el = meta.el.clone().expect("el always exists on the client");
-                    let Some(&prev_snapshot) = snapshots.get(k) else {
+                for meta in items.values_mut() {
+                    meta.refresh_el();
+
+                    // Hidden by the server until now, see `ssr_appear`.
+                    if prev.is_none() && ssr_appear {
+                        if let Some(el) = &meta.el {
+                            el.style().remove_property("visibility").unwrap();
+                        }
+                    }
+
+                    // Running animations can affect the layout (for example when they animate
+                    // the size), so they must be stopped before the goal snapshots are taken.
+                    for cur_anim in meta.cur_anims.drain(..) {
+                        cur_anim.cancel();
+                    }
+
+                    if let (Some(el), Some(hold)) = (&meta.el, meta.pending_job.take()) {
+                        release_anim_hold(el, hold);
+                    }
+                }
+
+                // Take all goal snapshots before starting any animations. Animations that change
+                // the size of an element (`animate_size`) immediately reflow the layout, which
+                // would falsify the snapshots of all following elements, especially in grids
+                // using `grid-auto-flow: dense`.
+                let new_snapshots = take_snapshots(items);
+
+                // Measured before any animations start, for the same reason as the snapshots.
+                let viewport_rects = if viewport_only {
+                    items
+                        .iter()
+                        .filter_map(|(k, meta)| {
+                            Some((k.clone(), meta.el.as_ref()?.get_bounding_client_rect()))
+                        })
+                        .collect::<HashMap<_, _>>()
+                } else {
+                    HashMap::new()
+                };
+                let viewport = viewport_only.then(viewport_extent);
+
+                if let Some(on_after_goal_snapshot) = on_after_goal_snapshot {
+                    on_after_goal_snapshot(());
+                }
+
+                // Whether the element, shifted by `offset`, is visible (or `viewport_only` is off).
+                let is_visible = |k: &K, offset: Position| {
+                    let (Some(viewport), Some(rect)) = (viewport, viewport_rects.get(k)) else {
+                        return true;
+                    };
+                    is_rect_in_viewport(rect, offset, viewport)
+                };
+
+                // For `slide_enter`, entering items are offset by the same amount that their
+                // closest existing neighbor (preferably the following one) has moved.
+                let enter_offsets = if slide_enter {
+                    alive_items.with_untracked(|alive_items| {
+                        let keys = alive_items.keys().collect::<Vec<_>>();
+
+                        let shift_of = |k: &K| {
+                            Some(snapshots.get(k)?.position - new_snapshots.get(k)?.position)
+                        };
+
+                        keys.iter()
+                            .enumerate()
+                            .filter(|(_, k)| !snapshots.contains_key(**k))
+                            .filter_map(|(i, k)| {
+                                let offset = keys[i + 1..]
+                                    .iter()
+                                    .find_map(|k| shift_of(k))
+                                    .or_else(|| keys[..i].iter().rev().find_map(|k| shift_of(k)))?;
+                                (offset != Position::default()).then(|| ((*k).clone(), offset))
+                            })
+                            .collect::<HashMap<_, _>>()
+                    })
+                } else {
+                    HashMap::new()
+                };
+
+                let mut jobs = Vec::new();
+
+                // Go through the items in their render order (instead of the order of the
+                // `HashMap`), so that the animations are started in a consistent order.
+                let keys = alive_items
+                    .with_untracked(|alive_items| alive_items.keys().cloned().collect::<Vec<_>>());
+
+                for (index, k) in keys.iter().enumerate() {
+                    let Some(meta) = items.get_mut(k) else {
+                        continue;
+                    };
+                    let (Some(el), Some(&new_snapshot)) = (meta.el.clone(), new_snapshots.get(k))
+                    else {
+                        continue;
+                    };
+
+                    meta.snapshot = Some(new_snapshot);
+
+                    let Some(mut prev_snapshot) = snapshots.get(k).copied() else {
+                        // Without a snapshot from before the change, there is nothing to move
+                        // from.
+                        if unresolved.contains(k) {
+                            continue;
+                        }
+
                         // Enter-animation
 
-                        if let Some(on_enter_start) = on_enter_start {
-                            on_enter_start(el.clone());
+                        if !is_visible(k, Position::default()) {
+                            continue;
                         }
 
-                        meta.cur_anim.take().map(|cur_anim| cur_anim.cancel());
+                        let reason = if prev.is_none() {
+                            EnterReason::Appear
+                        } else if resurrected.contains(k) {
+                            EnterReason::Resurrect
+                        } else {
+                            EnterReason::Add
+                        };
 
-                        meta.cur_anim =
-                            Some(enter_anim.with_value(|enter_anim| enter_anim.anim.animate(&el)));
+                        let slide_from = enter_offsets.get(k).map(|&offset| ElementSnapshot {
+                            position: new_snapshot.position + offset,
+                            ..new_snapshot
+                        });
 
+                        jobs.push((
+                            k.clone(),
+                            el,
+                            AnimJob::Enter {
+                                slide_from,
+                                to: new_snapshot,
+                                context: EnterContext {
+                                    reason,
+                                    index,
+                                    count: keys.len(),
+                                },
+                            },
+                        ));
                         continue;
                     };
 
                     // Move-animation
 
-                    meta.cur_anim.take().map(|cur_anim| cur_anim.cancel());
+                    if let Some(&offset) = in_flight.get(k) {
+                        prev_snapshot.position = prev_snapshot.position + offset;
+                    }
 
-                    let new_snapshot = get_el_snapshot(&el, animate_size, handle_margins);
+                    let ignored = ignore_keys.is_some_and(|ignore_keys| {
+                        ignore_keys.with_untracked(|ignore_keys| ignore_keys.contains(k))
+                    }) || !(is_visible(k, Position::default())
+                        || is_visible(k, prev_snapshot.position - new_snapshot.position));
 
-                    if prev_snapshot == new_snapshot {
+                    if ignored || !needs_move(prev_snapshot, new_snapshot) {
+                        if use_will_change {
+                            clear_will_change(&el);
+                        }
                         continue;
                     }
 
-                    meta.cur_anim = Some(move_anim.with_value(|move_anim| {
-                        move_anim
-                            .anim
-                            .animate(&el, prev_snapshot, new_snapshot, animate_size)
-                    }));
+                    jobs.push((
+                        k.clone(),
+                        el,
+                        AnimJob::Move {
+                            from: prev_snapshot,
+                            to: new_snapshot,
+                            reorder: reordered,
+                        },
+                    ));
+                }
+
+                let batch_size = batch_size.unwrap_or(usize::MAX).max(1);
+
+                // When the list got replaced, the new items may have to wait for the old ones.
+                let first_batch_size = if replaced_keys.is_some() {
+                    0
+                } else {
+                    batch_size
+                };
+                let deferred = jobs.split_off(first_batch_size.min(jobs.len()));
+
+                let mut start_job = |(k, el, job): (K, web_sys::HtmlElement, AnimJob)| {
+                    let anims = start_anim_job(&k, &el, job);
+                    record_job(&k, job, anims.first());
+                    if let Some(meta) = items.get_mut(&k) {
+                        meta.cur_anims.extend(anims);
+                    }
+                };
+
+                // Enter-animations that change the size of their item (such as
+                // `ExpandEnterAnimation`) already move the following items along, with the same
+                // timing. So they are started first, and the goals of the moves are measured again
+                // with the entering items in their starting state. The moves then only cover the
+                // remaining distance, for example from a reorder at the same time.
+                let (enters, mut moves): (Vec<_>, Vec<_>) = jobs
+                    .into_iter()
+                    .partition(|(_, _, job)| matches!(job, AnimJob::Enter { .. }));
+
+                let remeasure = !enters.is_empty() && !moves.is_empty();
+                enters.into_iter().for_each(&mut start_job);
+
+                if remeasure {
+                    moves = moves
+                        .into_iter()
+                        .filter_map(|(k, el, job)| {
+                            let AnimJob::Move { from, reorder, .. } = job else {
+                                return Some((k, el, job));
+                            };
+
+                            let to = take_snapshot(&el);
+                            if from == to {
+                                if use_will_change {
+                                    clear_will_change(&el);
+                                }
+                                return None;
+                            }
+
+                            Some((k, el, AnimJob::Move { from, to, reorder }))
+                        })
+                        .collect();
+                }
+
+                moves.into_iter().for_each(start_job);
+
+                for (k, _, job) in &deferred {
+                    record_job(k, *job, None);
+                }
+
+                if let Some(parent) = contained_parent {
+                    // The leave-animations may only start in the next frame.
+                    request_animation_frame(move || {
+                        remove_styles_when_finished(parent, vec!["contain"], move || {
+                            transition_id.try_get_value() == Some(id)
+                        });
+                    });
+                }
+
+                if let Some(on_transition_end) = on_transition_end {
+                    let mut anims = items
+                        .values()
+                        .flat_map(|meta| meta.cur_anims.iter().cloned())
+                        .collect::<Vec<_>>();
+
+                    // The leave-animations may only start in the next frame, see
+                    // `defer_leave_start`.
+                    request_animation_frame(move || {
+                        leaving_anims.try_with_value(|leaving_anims| {
+                            anims.extend(leaving_anims.values().cloned());
+                        });
+
+                        spawn_local(async move {
+                            for anim in &anims {
+                                wait_for(anim).await;
+                            }
+
+                            // The animations also end when they get cancelled on unmount.
+                            if leaving_anims.try_with_value(|_| ()).is_some() {
+                                on_transition_end(());
+                            }
+                        });
+                    });
+                }
+
+                if let Some(debug_record) = debug_record {
+                    debug_record(records.get_value());
+                }
+
+                if deferred.is_empty() {
+                    return;
+                }
+
+                let deferred_keys = deferred
+                    .into_iter()
+                    .map(|(k, el, job)| {
+                        let hold = hold_anim_start(&el, job);
+                        if let Some(meta) = items.get_mut(&k) {
+                            meta.pending_job = Some(hold);
+                        }
+                        k
+                    })
+                    .collect::<Vec<_>>();
+
+                let Some(replaced_keys) = replaced_keys else {
+                    start_in_batches(deferred_keys, batch_size, Rc::new(start_deferred_jobs));
+                    return;
+                };
+
+                let wait_for_leave = move || {
+                    let longest_leave = leaving_anims
+                        .try_with_value(|leaving_anims| {
+                            replaced_keys
+                                .iter()
+                                .filter_map(|k| anim_duration(leaving_anims.get(k)?))
+                                .max()
+                        })
+                        .flatten()
+                        .unwrap_or_default();
+
+                    set_timeout(
+                        move || {
+                            start_in_batches(
+                                deferred_keys,
+                                batch_size,
+                                Rc::new(start_deferred_jobs),
+                            )
+                        },
+                        longest_leave,
+                    );
+                };
+
+                // With `defer_leave_start`, the leave-animations only start in the next frame.
+                if defer_leave_start {
+                    request_animation_frame(wait_for_leave);
+                } else {
+                    wait_for_leave();
                 }
             });
         });
     });
 
-    let items_fn = move || {
+    // `alive_items` also changes when only the items themselves changed, so the keys are
+    // memoized to keep the inner `For` from re-diffing large lists whose keys stayed the same.
+    let rendered = create_memo(move |_| {
         alive_items.with(|items| {
             leaving_items.with(|leaving_items| {
-                items
-                    .keys()
-                    .chain(leaving_items.keys())
-                    .cloned()
-                    .collect::<Vec<_>>()
+                let keys = leaving_anchors.with_value(|anchors| {
+                    // Group the leaving items by the alive item they follow, in the order they
+                    // were rendered in. Those that don't follow any alive item go first.
+                    let mut following = HashMap::<Option<&K>, Vec<&K>>::new();
+                    let mut trailing = Vec::new();
+
+                    for (k, anchor) in anchors {
+                        if !leaving_items.contains_key(k) {
+                            continue;
+                        }
+                        match anchor {
+                            Some(anchor) if !items.contains_key(anchor) => trailing.push(k),
+                            anchor => following.entry(anchor.as_ref()).or_default().push(k),
+                        }
+                    }
+
+                    // Items that were never rendered have no previous position.
+                    trailing.extend(leaving_items.keys().filter(|k| !anchors.contains_key(*k)));
+
+                    let mut keys = Vec::with_capacity(items.len() + leaving_items.len());
+                    keys.extend(following.remove(&None).into_iter().flatten().cloned());
+                    for k in items.keys() {
+                        keys.push(k.clone());
+                        keys.extend(following.remove(&Some(k)).into_iter().flatten().cloned());
+                    }
+                    keys.extend(trailing.into_iter().cloned());
+                    keys
+                });
+
+                rendered_keys.set_value(keys.clone());
+                keys
             })
         })
-    };
+    });
 
     let children_fn = {
         {
@@ -616,21 +2706,47 @@ where
                     return ().into_view();
                 };
 
-                let view = view.into_view();
+                let mut view = view.into_view();
+
+                if is_server() && appear && ssr_appear {
+                    view = with_server_style(view, "visibility", "hidden");
+                }
 
                 let el = if is_server() {
                     None
                 } else {
-                    Some(extract_el_from_view(&view).expect("Could not extract element from view"))
+                    extract_el_from_view(&view)
+                        .ok()
+                        .or_else(|| find_first_element(&view))
                 };
 
+                #[cfg(debug_assertions)]
+                if let Some(el) = &el {
+                    warn_if_inline_custom_element(el.clone());
+                }
+
+                // The display is only known once the element has been mounted.
+                if el.is_some() {
+                    let k = k.clone();
+                    queue_microtask(move || {
+                        alive_items_meta.try_update_value(|items| {
+                            if let Some(meta) = items.get_mut(&k) {
+                                meta.el = meta.el.take().map(box_element);
+                            }
+                        });
+                    });
+                }
+
                 alive_items_meta.update_value(|meta| {
                     meta.insert(
                         k,
                         ItemMeta {
                             el,
+                            view: view.clone(),
                             scope,
-                            cur_anim: None,
+                            cur_anims: Vec::new(),
+                            snapshot: None,
+                            pending_job: None,
                         },
                     );
                 });
@@ -641,14 +2757,324 @@ where
     };
 
     view! {
-        <For each=items_fn.clone() key=move |k| k.clone() children=children_fn.clone() />
+        <For each=move || rendered.get() key=move |k| k.clone() children=children_fn.clone() />
+    }
+}
+
+/// The translation of the element by its running animations, read from its computed transform.
+/// Returns `None` if none of the animations is running.
+///
+/// Move-animations use `transform-origin: top left`, so the translation is exactly how far the
+/// element's top left corner is from its layout box. For other transforms this is approximate.
+fn in_flight_translation(el: &web_sys::HtmlElement, anims: &[Animation]) -> Option<Position> {
+    use web_sys::AnimationPlayState;
+
+    if !anims
+        .iter()
+        .any(|anim| anim.play_state() == AnimationPlayState::Running)
+    {
+        return None;
+    }
+
+    let transform = window()
+        .get_computed_style(el)
+        .ok()??
+        .get_property_value("transform")
+        .ok()?;
+
+    let (name, args) = transform.strip_suffix(')')?.split_once('(')?;
+    let values = args
+        .split(',')
+        .map(|v| v.trim().parse::<f64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    match (name, values.len()) {
+        ("matrix", 6) => Some(Position {
+            x: values[4],
+            y: values[5],
+        }),
+        ("matrix3d", 16) => Some(Position {
+            x: values[12],
+            y: values[13],
+        }),
+        _ => None,
+    }
+}
+
+/// Cancel the animations of the element, keeping the current values of the CSS `properties` as
+/// inline styles. Transforms are never kept, since they include the translation of an interrupted
+/// move, while leaving elements get positioned at their snapshot instead.
+fn cancel_keeping_values(el: &web_sys::HtmlElement, anims: Vec<Animation>, properties: &[String]) {
+    const TRANSFORMS: [&str; 4] = ["transform", "translate", "scale", "rotate"];
+
+    let computed = (!anims.is_empty() && !properties.is_empty())
+        .then(|| window().get_computed_style(el).ok().flatten())
+        .flatten();
+
+    let values = properties
+        .iter()
+        .filter(|property| !TRANSFORMS.contains(&property.as_str()))
+        .filter_map(|property| {
+            let value = computed.as_ref()?.get_property_value(property).ok()?;
+            Some((property, value))
+        })
+        .collect::<Vec<_>>();
+
+    for anim in anims {
+        anim.cancel();
+    }
+
+    let style = el.style();
+    for (property, value) in values {
+        style.set_property(property, &value).unwrap();
+    }
+}
+
+/// Collapse all cells of a leaving table row so that the following rows can move up.
+fn collapse_table_row(row: &web_sys::HtmlElement, options: &AnimateOptions) {
+    use wasm_bindgen::JsCast;
+
+    let keyframes: Array = [serde_wasm_bindgen::to_value(&CollapseCellKeyframe {
+        padding_top: "0px",
+        padding_bottom: "0px",
+        line_height: "0",
+        font_size: "0px",
+    })
+    .unwrap()]
+    .into_iter()
+    .collect();
+    let keyframes: js_sys::Object = keyframes.into();
+
+    // The row is removed at the end of its leave animation, so the cells need to stay collapsed
+    // until then, even if the browser removes the filling animations because of newer ones.
+    let options = AnimateOptions {
+        fill: FillMode::Forwards,
+        keep_when_replaced: true,
+        ..options.clone()
+    };
+
+    let cells = row.children();
+    for i in 0..cells.length() {
+        let Some(cell) = cells
+            .item(i)
+            .and_then(|cell| cell.dyn_into::<web_sys::HtmlElement>().ok())
+        else {
+            continue;
+        };
+
+        animate(&cell, Some(&keyframes), &options);
+    }
+}
+
+/// Calls `start` with up to `batch_size` of the keys per animation frame, starting with the next
+/// frame.
+fn start_in_batches<K: 'static>(mut keys: Vec<K>, batch_size: usize, start: Rc<dyn Fn(Vec<K>)>) {
+    request_animation_frame(move || {
+        let rest = keys.split_off(batch_size.min(keys.len()));
+        start(keys);
+
+        if !rest.is_empty() {
+            start_in_batches(rest, batch_size, start);
+        }
+    });
+}
+
+/// The inline property that [`hold_anim_start`] uses to hold the element.
+fn held_property(job: AnimJob) -> &'static str {
+    match job {
+        AnimJob::Enter { .. } => "visibility",
+        AnimJob::Move { .. } => "transform",
+    }
+}
+
+/// Keep an element in the state that its animation would start from until the animation actually
+/// starts. See `batch_size` on [`AnimatedFor`].
+fn hold_anim_start(el: &web_sys::HtmlElement, job: AnimJob) -> AnimHold {
+    let style = el.style();
+    let property = held_property(job);
+
+    let prev_style = (
+        style.get_property_value(property).unwrap(),
+        style.get_property_priority(property),
+    );
+
+    let value = match job {
+        AnimJob::Enter { .. } => "hidden".to_string(),
+        AnimJob::Move { from, to, .. } => {
+            let diff = from.position - to.position;
+            format!("translate({}px, {}px)", diff.x, diff.y)
+        }
+    };
+
+    style.set_property(property, &value).unwrap();
+
+    AnimHold { job, prev_style }
+}
+
+/// Undo [`hold_anim_start`], restoring the inline style that the element had before. Returns the
+/// job that was held.
+fn release_anim_hold(el: &web_sys::HtmlElement, hold: AnimHold) -> AnimJob {
+    let style = el.style();
+    let property = held_property(hold.job);
+    let (value, priority) = hold.prev_style;
+
+    if value.is_empty() {
+        style.remove_property(property).unwrap();
+    } else {
+        style
+            .set_property_with_priority(property, &value, &priority)
+            .unwrap();
+    }
+
+    hold.job
+}
+
+/// The duration that an animation was started with.
+pub(crate) fn anim_duration(anim: &Animation) -> Option<Duration> {
+    let timing = anim.effect()?.get_timing();
+
+    // Not yet supported by web_sys.
+    let duration = js_sys::Reflect::get(&timing, &"duration".into())
+        .ok()?
+        .as_f64()?;
+
+    Some(Duration::from_secs_f64(duration / 1000.0))
+}
+
+/// How long it takes until the animation ends, including its delay. `None` for animations that
+/// repeat forever.
+pub(crate) fn anim_remaining_time(anim: &Animation) -> Option<Duration> {
+    let timing = anim.effect()?.get_computed_timing();
+
+    // `ComputedEffectTiming` has no getters in web_sys.
+    let end_time = js_sys::Reflect::get(&timing, &"endTime".into())
+        .ok()?
+        .as_f64()
+        .filter(|end_time| end_time.is_finite())?;
+    let remaining = end_time - anim.current_time().unwrap_or_default();
+
+    Some(Duration::from_secs_f64(remaining.max(0.0) / 1000.0))
+}
+
+/// The size of the browser's viewport.
+fn viewport_extent() -> Extent {
+    let window = window();
+    let size = |v: Result<wasm_bindgen::JsValue, _>| v.ok().and_then(|v| v.as_f64());
+
+    Extent {
+        width: size(window.inner_width()).unwrap_or_default(),
+        height: size(window.inner_height()).unwrap_or_default(),
+    }
+}
+
+/// Whether the rect, shifted by `offset`, at least partially overlaps the viewport.
+fn is_rect_in_viewport(rect: &web_sys::DomRect, offset: Position, viewport: Extent) -> bool {
+    let left = rect.left() + offset.x;
+    let top = rect.top() + offset.y;
+
+    left < viewport.width
+        && left + rect.width() > 0.0
+        && top < viewport.height
+        && top + rect.height() > 0.0
+}
+
+/// The property on the element under which [`set_will_change`] keeps the inline `will-change`
+/// value and priority that the element had before.
+const PREV_WILL_CHANGE_KEY: &str = "__leptosAnimatePrevWillChange";
+
+/// Hint the browser that the element is about to be animated. The inline `will-change` that the
+/// element had before is remembered, so that [`clear_will_change`] can restore it.
+fn set_will_change(el: &web_sys::HtmlElement) {
+    let style = el.style();
+    let key = wasm_bindgen::JsValue::from_str(PREV_WILL_CHANGE_KEY);
+
+    // While the hint is still set from an earlier animation, the current value is our own.
+    if !js_sys::Reflect::has(el, &key).unwrap_or(false) {
+        let prev = Array::of2(
+            &style.get_property_value("will-change").unwrap().into(),
+            &style.get_property_priority("will-change").into(),
+        );
+        js_sys::Reflect::set(el, &key, &prev).unwrap();
+    }
+
+    style
+        .set_property("will-change", "transform, opacity")
+        .unwrap();
+}
+
+/// Remove the hint that was set using [`set_will_change`], restoring the inline `will-change` that
+/// the element had before. Does nothing if the hint isn't set.
+fn clear_will_change(el: &web_sys::HtmlElement) {
+    use wasm_bindgen::JsCast;
+
+    let key = wasm_bindgen::JsValue::from_str(PREV_WILL_CHANGE_KEY);
+    let Ok(prev) = js_sys::Reflect::get(el, &key) else {
+        return;
+    };
+    let Ok(prev) = prev.dyn_into::<Array>() else {
+        return;
+    };
+    js_sys::Reflect::delete_property(el, &key).unwrap();
+
+    let style = el.style();
+    let value = prev.get(0).as_string().unwrap_or_default();
+    let priority = prev.get(1).as_string().unwrap_or_default();
+
+    if value.is_empty() {
+        style.remove_property("will-change").unwrap();
+    } else {
+        style
+            .set_property_with_priority("will-change", &value, &priority)
+            .unwrap();
     }
 }
 
+/// Checks that the element is at its goal snapshot once the move-animation has finished. If it
+/// isn't, either the layout changed during the animation (for example because an image loaded),
+/// or the snapshots were measured in different coordinates than the animation was applied in.
+#[cfg(debug_assertions)]
+fn warn_if_off_goal(
+    anim: &Animation,
+    el: &web_sys::HtmlElement,
+    goal: ElementSnapshot,
+    take_snapshot: impl Fn(&web_sys::HtmlElement) -> ElementSnapshot + 'static,
+) {
+    use wasm_bindgen::JsCast;
+
+    const EPSILON: f64 = 1.0;
+
+    let closure = Closure::<dyn Fn(web_sys::Event)>::new({
+        let el = el.clone();
+        move |_| {
+            let actual = take_snapshot(&el);
+            let off = |a: f64, b: f64| (a - b).abs() > EPSILON;
+
+            if off(actual.position.x, goal.position.x)
+                || off(actual.position.y, goal.position.y)
+                || off(actual.extent.width, goal.extent.width)
+                || off(actual.extent.height, goal.extent.height)
+            {
+                logging::warn!(
+                    "AnimatedFor: <{}> ended its move at {:?} instead of its goal {:?}. Either \
+                     the layout changed during the animation, or the measurements are off, for \
+                     example because of a transformed ancestor or a scroll container.",
+                    el.tag_name().to_lowercase(),
+                    actual,
+                    goal
+                );
+            }
+        }
+    })
+    .into_js_value();
+
+    // `onfinish` may already be taken by the user of the animation.
+    anim.add_event_listener_with_callback("finish", closure.unchecked_ref())
+        .unwrap();
+}
+
 /// Get the node ref from a view. Ideally we'd like to have refs to the comment node or something
 /// that this view represents, but that's currently not possible.
-fn extract_el_from_view(view: &View) -> anyhow::Result<web_sys::HtmlElement> {
-    use wasm_bindgen::JsCast;
+pub(crate) fn extract_el_from_view(view: &View) -> anyhow::Result<web_sys::HtmlElement> {
     match view {
         View::Component(component) => {
             let node_view = component
@@ -658,14 +3084,9 @@ fn extract_el_from_view(view: &View) -> anyhow::Result<web_sys::HtmlElement> {
             extract_el_from_view(node_view)
         }
         View::Element(view) => {
-            let el = view
-                .clone()
-                .into_html_element()
-                .dyn_ref::<web_sys::HtmlElement>()
-                .ok_or_else(|| {
-                    anyhow::anyhow!("Could not convert leptos::HtmlElement to web_sys::HtmlElement")
-                })?
-                .clone();
+            let el = as_animatable(&view.clone().into_html_element()).ok_or_else(|| {
+                anyhow::anyhow!("Could not convert leptos::HtmlElement to web_sys::HtmlElement")
+            })?;
 
             Ok(el)
         }
@@ -676,20 +3097,166 @@ fn extract_el_from_view(view: &View) -> anyhow::Result<web_sys::HtmlElement> {
     }
 }
 
+/// Adds an inline style to the element of the view on the server, so that it is already part of
+/// the server-rendered HTML, for example to hide the element until it appears, see `ssr_appear`.
+pub(crate) fn with_server_style(view: View, property: &'static str, value: &'static str) -> View {
+    match view {
+        View::Element(el) => el.into_html_element().style(property, value).into_view(),
+        View::Component(mut component) => {
+            if let Some(first) = component.children.first_mut() {
+                *first = with_server_style(std::mem::take(first), property, value);
+            }
+            View::Component(component)
+        }
+        view => view,
+    }
+}
+
+/// Returns the node as an element that can be animated: an HTML element or an element inside of
+/// an `<svg>`.
+///
+/// SVG elements are passed around as `web_sys::HtmlElement` as well, since all the methods that
+/// are used on them (`style`, `animate`, `getBoundingClientRect`, ...) exist on both and are
+/// looked up dynamically. Only the offset-properties are missing, see [`get_el_snapshot`]. The
+/// public callbacks and `EnterAnimation::enter` receive them as `web_sys::Element` instead.
+fn as_animatable(node: &wasm_bindgen::JsValue) -> Option<web_sys::HtmlElement> {
+    use wasm_bindgen::JsCast;
+
+    if let Some(el) = node.dyn_ref::<web_sys::HtmlElement>() {
+        return Some(el.clone());
+    }
+
+    let el = node.dyn_ref::<web_sys::Element>()?;
+    is_svg(el).then(|| el.clone().unchecked_into())
+}
+
+/// Custom elements are `display:inline` by default, which makes them ignore the transforms of the
+/// move-animations. The display is checked once the element has been mounted.
+#[cfg(debug_assertions)]
+fn warn_if_inline_custom_element(el: web_sys::HtmlElement) {
+    // The names of custom elements must contain a hyphen, unlike those of built-in elements.
+    if !el.tag_name().contains('-') {
+        return;
+    }
+
+    queue_microtask(move || {
+        let display = window()
+            .get_computed_style(&el)
+            .ok()
+            .flatten()
+            .and_then(|style| style.get_property_value("display").ok());
+
+        if display.as_deref() == Some("inline") {
+            logging::warn!(
+                "AnimatedFor: the custom element <{}> is `display:inline`, so it can't be moved. \
+                 Give it a different `display`, for example using `:host {{ display: block }}`.",
+                el.tag_name().to_lowercase()
+            );
+        }
+    });
+}
+
+/// Elements with `display:contents` don't have a box of their own, so they can't be measured or
+/// animated. For those, this returns their first descendant that has a box instead, following
+/// the first element child of each wrapper.
+fn box_element(el: web_sys::HtmlElement) -> web_sys::HtmlElement {
+    let is_contents = |el: &web_sys::HtmlElement| {
+        window()
+            .get_computed_style(el)
+            .ok()
+            .flatten()
+            .and_then(|style| style.get_property_value("display").ok())
+            .as_deref()
+            == Some("contents")
+    };
+
+    let mut cur = el.clone();
+    while is_contents(&cur) {
+        let Some(child) = cur
+            .first_element_child()
+            .and_then(|child| as_animatable(&child))
+        else {
+            logging::warn!(
+                "AnimatedFor: <{}> is `display:contents` and has no child element that could be \
+                 animated instead. Render an element with a box as the top level of the item.",
+                cur.tag_name().to_lowercase()
+            );
+            return el;
+        };
+        cur = child;
+    }
+
+    cur
+}
+
+/// Whether the element is an SVG element (including the `<svg>` itself).
+fn is_svg(el: &web_sys::Element) -> bool {
+    el.namespace_uri().as_deref() == Some("http://www.w3.org/2000/svg")
+}
+
+/// Fallback for [`extract_el_from_view`] that walks the DOM nodes of the view and returns the
+/// first one that can be animated. This works for views such as `DynChild` or `Each`, whose
+/// elements can't be extracted from the view itself.
+pub(crate) fn find_first_element(view: &View) -> Option<web_sys::HtmlElement> {
+    use leptos::leptos_dom::Mountable;
+
+    let closing = view.get_closing_node();
+    let mut node = Some(view.get_opening_node());
+
+    while let Some(cur) = node {
+        if let Some(el) = as_animatable(&cur) {
+            return Some(el);
+        }
+        if cur == closing {
+            break;
+        }
+        node = cur.next_sibling();
+    }
+
+    None
+}
+
 /// Take a snapshot of an element's position and (optionally) size.
+///
+/// If `device_pixel_ratio` is set, the extent is rounded to whole device pixels. On screens with a
+/// fractional ratio (like 1.25 or 1.5), `getBoundingClientRect` returns sizes that are off by
+/// fractions of a pixel depending on where the element is placed, which would otherwise be
+/// detected as a size change. After rounding, sizes either match exactly or differ by at least one
+/// device pixel.
+///
+/// If `compensate_scale` is set, the measurements that are taken in screen space (the extent and
+/// the position relative to a `reference`) are divided by the scale of the container (the
+/// reference or the offset parent), so that they are in the same space as the transforms that
+/// are applied to the element.
 fn get_el_snapshot(
     el: &web_sys::HtmlElement,
     record_extent: bool,
     handle_margins: bool,
+    device_pixel_ratio: Option<f64>,
+    reference: Option<&web_sys::Element>,
+    compensate_scale: bool,
 ) -> ElementSnapshot {
+    let snap = |v: f64| match device_pixel_ratio {
+        Some(dpr) => (v * dpr).round() / dpr,
+        None => v,
+    };
+
+    let scale = (compensate_scale && (record_extent || reference.is_some()))
+        .then(|| match reference {
+            Some(reference) => rendered_scale(reference),
+            None => rendered_scale(&el.offset_parent()?),
+        })
+        .flatten()
+        .unwrap_or(Position { x: 1.0, y: 1.0 });
+
     let extent = record_extent
         .then(|| {
             // We're using GetBoundingClientRect here because offsetWidth/Height aren't truthful
             // when it comes to paddings.
             let rect = el.get_bounding_client_rect();
             Extent {
-                width: rect.width(),
-                height: rect.height(),
+                width: snap(rect.width()) / scale.x,
+                height: snap(rect.height()) / scale.y,
             }
         })
         .unwrap_or_default();
@@ -699,11 +3266,32 @@ fn get_el_snapshot(
         el.style().set_property("margin", "0px").unwrap();
     }
 
-    // We're not using GetBoundingClientRect here because the position it returns is in viewport
-    // space, but we need it for position:absolute.
-    let position = Position {
-        x: el.offset_left() as f64,
-        y: el.offset_top() as f64,
+    // SVG elements have no offset-properties, so they are measured relative to the `<svg>` they
+    // are in instead.
+    let owner_svg = (reference.is_none() && is_svg(el))
+        .then(|| el.parent_element()?.closest("svg").ok().flatten())
+        .flatten();
+    let reference = reference.or(owner_svg.as_ref());
+
+    let position = match reference {
+        // Relative to the padding box of the reference, in the same space as offsetLeft/Top
+        // would be if the reference was the offset parent.
+        Some(reference) => {
+            let rect = el.get_bounding_client_rect();
+            let reference_rect = reference.get_bounding_client_rect();
+            Position {
+                x: (rect.left() - reference_rect.left()) / scale.x - reference.client_left() as f64
+                    + reference.scroll_left() as f64,
+                y: (rect.top() - reference_rect.top()) / scale.y - reference.client_top() as f64
+                    + reference.scroll_top() as f64,
+            }
+        }
+        // We're not using GetBoundingClientRect here because the position it returns is in
+        // viewport space, but we need it for position:absolute.
+        None => Position {
+            x: el.offset_left() as f64,
+            y: el.offset_top() as f64,
+        },
     };
 
     if handle_margins {
@@ -712,3 +3300,30 @@ fn get_el_snapshot(
 
     ElementSnapshot { position, extent }
 }
+
+/// How much the element is scaled on screen by the transforms of itself and its ancestors,
+/// comparing its bounding rect with its layout size. Returns `None` for elements without a
+/// layout size (like SVG elements) and if the difference could just be rounding.
+fn rendered_scale(el: &web_sys::Element) -> Option<Position> {
+    use wasm_bindgen::JsCast;
+
+    let el = el.dyn_ref::<web_sys::HtmlElement>()?;
+    let rect = el.get_bounding_client_rect();
+    let (width, height) = (el.offset_width() as f64, el.offset_height() as f64);
+
+    // offsetWidth/Height are rounded to whole pixels.
+    let ratio = |rendered: f64, layout: f64| {
+        if layout == 0.0 || (rendered - layout).abs() <= 1.0 {
+            1.0
+        } else {
+            rendered / layout
+        }
+    };
+
+    let scale = Position {
+        x: ratio(rect.width(), width),
+        y: ratio(rect.height(), height),
+    };
+
+    (scale.x != 1.0 || scale.y != 1.0).then_some(scale)
+}
@@ -1,10 +1,21 @@
 use leptos::*;
 
+use crate::animate::{border_box_extent, keyframe_to_js, remove_styles_when_finished};
+use crate::animated_for::{anim_remaining_time, extract_el_from_view};
+use crate::animated_swap::supports_view_transitions;
 use crate::{
-    AnimatedFor, AnyEnterAnimation, AnyLeaveAnimation, AnyMoveAnimation, FadeAnimation,
-    SlidingAnimation,
+    animate, AnimateOptions, AnimatedFor, AnyEnterAnimation, AnyLeaveAnimation, AnyMoveAnimation,
+    AnyResizeAnimation, EnterContext, FadeAnimation, SlidingAnimation,
 };
+use std::collections::HashMap;
 use std::hash::Hash;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+/// Used to give each entry a unique `view-transition-name`, see `use_view_transition` on
+/// [`AnimatedLayout`].
+static NEXT_VIEW_TRANSITION_NAME: AtomicUsize = AtomicUsize::new(0);
 
 /// Part of the return value for [`AnimatedLayout`] describing each individual view.
 pub struct LayoutEntry<K: Hash + Eq + Clone + 'static> {
@@ -13,6 +24,46 @@ pub struct LayoutEntry<K: Hash + Eq + Clone + 'static> {
 
     /// A function that will be called to create the view.
     pub view_fn: Box<dyn Fn() -> View>,
+
+    /// Enter-animation for this view, used instead of the `enter_anim` of the [`AnimatedLayout`]
+    /// if the view enters with this layout. Wrap it in a [`Delay`][crate::Delay] to let the
+    /// views of a layout enter one after another.
+    pub enter_anim: Option<AnyEnterAnimation>,
+
+    /// Leave-animation for this view, used instead of the `leave_anim` of the [`AnimatedLayout`]
+    /// when the view leaves with the next layout.
+    pub leave_anim: Option<AnyLeaveAnimation>,
+
+    /// Move-animation for this view, used instead of the `move_anim` of the [`AnimatedLayout`]
+    /// while this layout is shown.
+    pub move_anim: Option<AnyMoveAnimation>,
+}
+
+impl<K: Hash + Eq + Clone + 'static> LayoutEntry<K> {
+    pub fn new(key: K, view_fn: impl Fn() -> View + 'static) -> Self {
+        Self {
+            key,
+            view_fn: Box::new(view_fn),
+            enter_anim: None,
+            leave_anim: None,
+            move_anim: None,
+        }
+    }
+
+    pub fn enter_anim(mut self, enter_anim: impl Into<AnyEnterAnimation>) -> Self {
+        self.enter_anim = Some(enter_anim.into());
+        self
+    }
+
+    pub fn leave_anim(mut self, leave_anim: impl Into<AnyLeaveAnimation>) -> Self {
+        self.leave_anim = Some(leave_anim.into());
+        self
+    }
+
+    pub fn move_anim(mut self, move_anim: impl Into<AnyMoveAnimation>) -> Self {
+        self.move_anim = Some(move_anim.into());
+        self
+    }
 }
 
 /// The return value for [`AnimatedLayout`], containing the new class being set and the list of
@@ -51,17 +102,129 @@ pub fn AnimatedLayout<K, ContentsFn>(
     /// See this prop on [`AnimatedFor`].
     #[prop(default = SlidingAnimation::default().into(), into)]
     move_anim: AnyMoveAnimation,
+
+    /// The `overflow` to set on the container while a transition is running, for example
+    /// `hidden` or `clip`. Leaving and moving elements can otherwise overflow the container during
+    /// the transition and cause scrollbars to flash.
+    ///
+    /// The inline style is removed again once all animations inside the container have finished.
+    #[prop(optional, into)]
+    transition_overflow: Option<Oco<'static, str>>,
+
+    /// See this prop on [`AnimatedFor`]. It is set on the container of this component.
+    #[prop(optional, into)]
+    transition_contain: Option<Oco<'static, str>>,
+
+    /// CSS `transition` to set on the container, for example `background-color 300ms ease-out`.
+    /// This lets visual changes implied by the class swap ease instead of snapping, while the
+    /// children are animated at the same time.
+    ///
+    /// Only transition properties that don't affect the layout of the children (such as colors,
+    /// shadows or borders that keep their width). The children take their goal snapshots right
+    /// after the class has been changed, so transitioning for example `grid-template-columns`
+    /// or `gap` would animate them towards wrong positions.
+    #[prop(optional, into)]
+    container_transition: Option<Oco<'static, str>>,
+
+    /// Cross-fade the background of the container when the class changes, until the last
+    /// animation of the children has ended (including its delay). Without it, a new background
+    /// color or image snaps while the children are still moving.
+    ///
+    /// The old `background-color` and `background-image` are copied onto an overlay `<div>` that
+    /// fades out. The overlay is an additional, hidden child of the container after the entries
+    /// (keep that in mind for selectors like `:last-child`). While it is shown, it is positioned
+    /// absolutely behind the entries, so the container must be positioned (for example
+    /// `position: relative`), and it gets `isolation: isolate` until the transition has finished.
+    /// Other visual changes, like borders or shadows, are not covered; use `container_transition`
+    /// for those.
+    #[prop(default = false)]
+    fade_container: bool,
+
+    /// Animation for the size of the container when it changes with the layout, for example
+    /// when the new layout has more rows. Without it, the container snaps to its new size while
+    /// the children are still moving.
+    ///
+    /// The size is animated using `width` and `height`, so this works best for layouts whose
+    /// columns don't depend on the width of the container, otherwise the children reflow during
+    /// the animation.
+    #[prop(optional, into)]
+    container_resize_anim: Option<AnyResizeAnimation>,
+
+    /// Use the browser's native
+    /// [View Transitions API](https://developer.mozilla.org/en-US/docs/Web/API/View_Transitions_API)
+    /// if it is available, and fall back to the regular animations otherwise.
+    ///
+    /// With view transitions, the animations of the entries and of the container are bypassed
+    /// (`enter_anim`, `leave_anim`, `move_anim` and the ones of the [`LayoutEntry`]s, as well as
+    /// `transition_overflow`, `transition_contain`, `fade_container` and
+    /// `container_resize_anim`). Instead, each entry gets a unique `view-transition-name`, so the
+    /// browser moves and resizes the entries that stay from their old place to their new one, and
+    /// cross-fades the ones that enter or leave. This can be customized in CSS using the
+    /// `::view-transition-group(*)`, `::view-transition-old(*)` and `::view-transition-new(*)`
+    /// pseudo-elements. Entries whose element can't be extracted from their view are part of the
+    /// `root` snapshot instead.
+    ///
+    /// Support is detected on the client, so the server can't know which markup to render. Don't
+    /// use this together with hydration.
+    #[prop(default = false)]
+    use_view_transition: bool,
 ) -> impl IntoView
 where
     K: Hash + Eq + Clone + 'static,
     ContentsFn: Fn() -> LayoutResult<K> + 'static,
 {
+    if use_view_transition && supports_view_transitions() {
+        return view_transition_layout(contents, container_transition);
+    }
+
     let new_class = StoredValue::new(None::<Oco<'static, str>>);
     let class = RwSignal::new(None::<Oco<'static, str>>);
 
+    let container = NodeRef::<html::Div>::new();
+    let overlay = NodeRef::<html::Div>::new();
+    let restore_contain = transition_contain.is_some();
+
+    // The `enter_anim` of the entries of the latest layout, by their index.
+    let entry_enter_anims = StoredValue::new(Vec::<Option<AnyEnterAnimation>>::new());
+
+    // The `leave_anim` and `move_anim` of the entries of the latest layout and of the one before
+    // it, whose entries are the ones that leave.
+    let entry_anims = StoredValue::new(HashMap::<K, EntryAnims>::new());
+    let prev_entry_anims = StoredValue::new(HashMap::<K, EntryAnims>::new());
+
     let each = move || {
-        let contents = contents();
+        let mut contents = contents();
         new_class.set_value(contents.class);
+        entry_enter_anims.set_value(
+            contents
+                .entries
+                .iter_mut()
+                .map(|entry| entry.enter_anim.take())
+                .collect(),
+        );
+
+        let anims = contents
+            .entries
+            .iter_mut()
+            .map(|entry| {
+                let anims = EntryAnims {
+                    leave_anim: entry.leave_anim.take(),
+                    move_anim: entry.move_anim.take(),
+                };
+                (entry.key.clone(), anims)
+            })
+            .collect();
+        let prev = entry_anims
+            .try_update_value(|entry_anims| std::mem::replace(entry_anims, anims))
+            .unwrap_or_default();
+        prev_entry_anims.set_value(prev);
+
+        // This runs before `AnimatedFor` takes its snapshots, which must already be affected by
+        // the containment.
+        if let (Some(contain), Some(container)) = (&transition_contain, container.get_untracked()) {
+            container.style().set_property("contain", contain).unwrap();
+        }
+
         contents.entries
     };
 
@@ -69,8 +232,90 @@ where
 
     let children = move |v: &LayoutEntry<K>| (v.view_fn)();
 
+    let enter_anim_by_context = Callback::new(move |context: EnterContext| {
+        entry_enter_anims
+            .try_update_value(|anims| anims.get_mut(context.index).and_then(Option::take))
+            .flatten()
+    });
+
+    let leave_anim_by_key = Callback::new(move |k: K| {
+        prev_entry_anims
+            .try_with_value(|anims| anims.get(&k)?.leave_anim.clone())
+            .flatten()
+    });
+
+    let move_anim_by_key = Callback::new(move |k: K| {
+        entry_anims
+            .try_with_value(|anims| anims.get(&k)?.move_anim.clone())
+            .flatten()
+    });
+
+    // Incremented on each transition, so that only the last one restores the overflow.
+    let transition_id = StoredValue::new(0usize);
+
     let on_after_snapshot = Callback::new(move |_| {
+        let Some(container) = container.get_untracked() else {
+            class.set(new_class.get_value());
+            return;
+        };
+        let container: web_sys::HtmlElement = (*container).clone().into();
+
+        let resize = container_resize_anim
+            .clone()
+            .map(|resize_anim| (resize_anim, border_box_extent(&container)));
+
+        let old_background = fade_container.then(|| background(&container));
+
         class.set(new_class.get_value());
+
+        if let Some(overflow) = &transition_overflow {
+            container
+                .style()
+                .set_property("overflow", overflow)
+                .unwrap();
+        }
+
+        transition_id.update_value(|id| *id += 1);
+        let id = transition_id.get_value();
+        let restore_overflow = transition_overflow.is_some();
+
+        if resize.is_none() && !restore_overflow && !restore_contain && old_background.is_none() {
+            return;
+        }
+
+        // `AnimatedFor` starts its animations in a microtask that is queued right after this
+        // callback, so we need to wait for that one to run first.
+        queue_microtask(move || {
+            queue_microtask(move || {
+                if let Some((resize_anim, from)) = resize {
+                    let to = border_box_extent(&container);
+                    if from != to {
+                        resize_anim.animate(&container, from, to);
+                    }
+                }
+
+                let mut properties = [
+                    restore_overflow.then_some("overflow"),
+                    restore_contain.then_some("contain"),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+
+                if let (Some(old_background), Some(overlay)) =
+                    (old_background, overlay.get_untracked())
+                {
+                    let overlay: web_sys::HtmlElement = (*overlay).clone().into();
+                    properties.extend(fade_out_background(&container, &overlay, old_background));
+                }
+
+                if !properties.is_empty() {
+                    remove_styles_when_finished(container, properties, move || {
+                        transition_id.try_get_value() == Some(id)
+                    });
+                }
+            });
+        });
     });
 
     let inner = view! {
@@ -81,14 +326,181 @@ where
             on_after_snapshot
             animate_size=true
             enter_anim
+            enter_anim_by_context
+            leave_anim_by_key
+            move_anim_by_key
             move_anim
             leave_anim
         />
     };
 
     view! {
-        <div class=class>
+        <div class=class node_ref=container style:transition=container_transition>
             {inner}
+            // Rendered next to the entries rather than added later, so that it doesn't get in the
+            // way of the DOM updates of the entries.
+            {fade_container.then(|| view! { <div node_ref=overlay style=OVERLAY_STYLE></div> })}
         </div>
     }
+    .into_view()
+}
+
+/// Implementation of [`AnimatedLayout`] using the View Transitions API. The class and the entries
+/// are swapped inside the update callback of the view transition.
+fn view_transition_layout<K, ContentsFn>(
+    contents: ContentsFn,
+    container_transition: Option<Oco<'static, str>>,
+) -> View
+where
+    K: Hash + Eq + Clone + 'static,
+    ContentsFn: Fn() -> LayoutResult<K> + 'static,
+{
+    let class = RwSignal::new(None::<Oco<'static, str>>);
+    let entries = StoredValue::new(Vec::<LayoutEntry<K>>::new());
+    let render = create_trigger();
+
+    // The latest contents, until they are rendered. If the contents change again before the update
+    // callback of the view transition has run, it renders the newer ones.
+    let next = StoredValue::new(None::<LayoutResult<K>>);
+
+    let update = move || {
+        let Some(Some(contents)) = next.try_update_value(Option::take) else {
+            return;
+        };
+        class.set(contents.class);
+        entries.set_value(contents.entries);
+        render.notify();
+    };
+
+    create_effect(move |prev: Option<()>| {
+        next.set_value(Some(contents()));
+
+        if prev.is_none() {
+            update();
+            return;
+        }
+
+        let callback = Closure::once_into_js(update);
+        if document()
+            .start_view_transition_with_update_callback(Some(callback.unchecked_ref()))
+            .is_err()
+        {
+            update();
+        }
+    });
+
+    let each = move || {
+        render.track();
+        entries.try_update_value(std::mem::take).unwrap_or_default()
+    };
+
+    let key = move |entry: &LayoutEntry<K>| entry.key.clone();
+
+    // The browser matches the old and the new state of an entry by its name, which must be unique
+    // in the whole document.
+    let children = move |entry: LayoutEntry<K>| {
+        let view = (entry.view_fn)();
+        if let Ok(el) = extract_el_from_view(&view) {
+            let name = format!(
+                "leptos-animate-{}",
+                NEXT_VIEW_TRANSITION_NAME.fetch_add(1, Ordering::Relaxed)
+            );
+            el.style()
+                .set_property("view-transition-name", &name)
+                .unwrap();
+        }
+        view
+    };
+
+    view! {
+        <div class=class style:transition=container_transition>
+            <For each key children />
+        </div>
+    }
+    .into_view()
+}
+
+/// The animations of a [`LayoutEntry`] that [`AnimatedFor`] looks up by the key of the entry.
+struct EntryAnims {
+    leave_anim: Option<AnyLeaveAnimation>,
+    move_anim: Option<AnyMoveAnimation>,
+}
+
+/// The `background-color` and `background-image` of an element.
+fn background(el: &web_sys::HtmlElement) -> (String, String) {
+    let Some(style) = window().get_computed_style(el).ok().flatten() else {
+        return Default::default();
+    };
+    let value = |property| style.get_property_value(property).unwrap_or_default();
+
+    (value("background-color"), value("background-image"))
+}
+
+/// The overlay of `fade_container`, which is hidden until a transition shows it.
+const OVERLAY_STYLE: &str = "display: none; position: absolute; inset: 0; z-index: -1; \
+                             pointer-events: none; border-radius: inherit";
+
+#[derive(serde::Serialize)]
+struct OverlayKeyframe {
+    opacity: f64,
+}
+
+/// Shows the `overlay` with the old background of the container and fades it out, see
+/// `fade_container` on [`AnimatedLayout`]. Returns the inline styles that got set on the container.
+fn fade_out_background(
+    container: &web_sys::HtmlElement,
+    overlay: &web_sys::HtmlElement,
+    (color, image): (String, String),
+) -> Vec<&'static str> {
+    let mut options = web_sys::GetAnimationsOptions::new();
+    options.subtree(true);
+
+    let Some(duration) = container
+        .get_animations_with_options(&options)
+        .iter()
+        .filter_map(|anim| anim_remaining_time(&anim.unchecked_into()))
+        .max()
+    else {
+        return Vec::new();
+    };
+
+    let overlay_style = overlay.style();
+    for (property, value) in [
+        ("display", "block"),
+        ("background-color", &color),
+        ("background-image", &image),
+    ] {
+        overlay_style.set_property(property, value).unwrap();
+    }
+
+    // Keeps the overlay above the background of the container, but behind its children.
+    container
+        .style()
+        .set_property("isolation", "isolate")
+        .unwrap();
+
+    let keyframes: web_sys::js_sys::Array = [1.0, 0.0]
+        .into_iter()
+        .map(|opacity| keyframe_to_js(&OverlayKeyframe { opacity }))
+        .collect();
+    let anim = animate(
+        overlay,
+        Some(&keyframes.into()),
+        &AnimateOptions::new(duration),
+    );
+
+    let closure = Closure::<dyn Fn(web_sys::Event)>::new({
+        let overlay = overlay.clone();
+        move |_| {
+            // A newer transition may be fading it out again already.
+            if overlay.get_animations().length() == 0 {
+                overlay.style().set_property("display", "none").unwrap();
+            }
+        }
+    })
+    .into_js_value();
+    anim.set_onfinish(Some(closure.unchecked_ref()));
+    anim.set_oncancel(Some(closure.unchecked_ref()));
+
+    vec!["isolation"]
 }
@@ -0,0 +1,134 @@
+use leptos::*;
+use web_sys::js_sys;
+
+use crate::dynamics::SecondOrderDynamics;
+
+/// The longest time step of a single simulation step. The simulation gets unstable for large
+/// steps, so longer frames are split up.
+const MAX_STEP: f64 = 1.0 / 120.0;
+
+/// Renders a number that counts towards its new value whenever `value` changes, using a
+/// [`SecondOrderDynamics`] simulation. The parameters work the same as on
+/// [`DynamicsAnimation`][crate::DynamicsAnimation].
+///
+/// The text is updated in every animation frame while the number is moving, so this is meant for
+/// a handful of numbers like counters or totals, not for large tables.
+///
+/// # Example
+/// ```
+/// let format = Callback::new(|v: f64| {
+///     let digits = format!("{:.0}", v.abs());
+///     let grouped = digits
+///         .as_bytes()
+///         .rchunks(3)
+///         .rev()
+///         .map(|chunk| std::str::from_utf8(chunk).unwrap())
+///         .collect::<Vec<_>>()
+///         .join(",");
+///     if v <= -0.5 { format!("-{grouped}") } else { grouped }
+/// });
+///
+/// view! {
+///     <AnimatedNumber value=count format />
+/// }
+/// ```
+#[component]
+pub fn AnimatedNumber(
+    /// The number to display. Changes are animated.
+    #[prop(into)]
+    value: Signal<f64>,
+
+    /// Function that turns the current (in-between) value into the displayed text. Defaults to
+    /// rounding to a whole number.
+    #[prop(optional)]
+    format: Option<Callback<f64, String>>,
+
+    /// Frequency of the simulation; the response speed.
+    #[prop(default = 2.0)]
+    frequency: f32,
+
+    /// Damping ratio: below 1 the number overshoots and wobbles before settling, above 1 it
+    /// approaches the target more slowly.
+    #[prop(default = 1.0)]
+    damping: f32,
+
+    /// Initial response: 0 starts slowly, above 1 overshoots, negative values anticipate.
+    #[prop(default = 0.0)]
+    response: f32,
+
+    /// Class of the `span` that contains the number.
+    #[prop(optional, into)]
+    class: Option<Oco<'static, str>>,
+) -> impl IntoView {
+    let displayed = RwSignal::new(value.get_untracked());
+    let dynamics = StoredValue::new(SecondOrderDynamics::new(
+        frequency,
+        damping,
+        response,
+        value.get_untracked(),
+    ));
+    let running = StoredValue::new(false);
+
+    create_effect(move |prev: Option<()>| {
+        value.track();
+
+        if prev.is_none() || running.get_value() {
+            return;
+        }
+
+        running.set_value(true);
+        run_number_dynamics(value, displayed, dynamics, running, js_sys::Date::now());
+    });
+
+    let text = move || {
+        let v = displayed.get();
+        match format {
+            Some(format) => format(v),
+            None => format!("{v:.0}"),
+        }
+    };
+
+    view! { <span class=class>{text}</span> }
+}
+
+/// Steps the simulation once per animation frame until it settles on the current value.
+fn run_number_dynamics(
+    value: Signal<f64>,
+    displayed: RwSignal<f64>,
+    dynamics: StoredValue<SecondOrderDynamics<f64>>,
+    running: StoredValue<bool>,
+    last_frame: f64,
+) {
+    request_animation_frame(move || {
+        let now = js_sys::Date::now();
+        let Some(goal) = value.try_get_untracked() else {
+            return;
+        };
+
+        // Background tabs don't get any frames, so the time step can get very long.
+        let dt = ((now - last_frame) / 1000.0).clamp(0.001, 1.0);
+        let steps = (dt / MAX_STEP).ceil().max(1.0);
+
+        let Some((y, settled)) = dynamics.try_update_value(|dynamics| {
+            for _ in 0..steps as usize {
+                dynamics.update(goal, (dt / steps) as f32);
+            }
+
+            let y = dynamics.get();
+            (
+                y,
+                (goal - y).abs() < 1e-3 && dynamics.velocity().abs() < 1e-3,
+            )
+        }) else {
+            return;
+        };
+
+        if settled {
+            displayed.set(goal);
+            running.set_value(false);
+        } else {
+            displayed.set(y);
+            run_number_dynamics(value, displayed, dynamics, running, now);
+        }
+    });
+}
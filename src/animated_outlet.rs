@@ -0,0 +1,131 @@
+use crate::{AnimatedFor, AnyEnterAnimation, AnyLeaveAnimation, FadeAnimation, Position};
+use leptos::leptos_dom::helpers::{TimeoutHandle, WindowListenerHandle};
+use leptos::*;
+use leptos_router::{use_route, RouteContext};
+use std::collections::HashMap;
+use std::time::Duration;
+use wasm_bindgen::JsCast;
+
+/// How long after a leave-animation started we keep compensating for the window scrolling.
+const SCROLL_COMPENSATION_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Animated version of leptos_router's [`<Outlet />`][leptos_router::Outlet]. Navigating between
+/// routes cross-animates the page content.
+///
+/// The pages are keyed by the matched path of the child route (including its params), so
+/// navigating from `/contacts/1` to `/contacts/2` animates as well.
+///
+/// To animate back navigation in the opposite direction, use a
+/// [`SlideAnimation`][crate::SlideAnimation] with a direction signal as the enter and leave
+/// animation.
+///
+/// Like [`AnimatedSwap`][crate::AnimatedSwap], the leaving page is set to `position:absolute`, so
+/// the element containing the outlet should usually have `position:relative`.
+///
+/// Requires the `router` feature.
+#[component]
+pub fn AnimatedOutlet(
+    /// See this prop on [`AnimatedFor`].
+    #[prop(default = false)]
+    appear: bool,
+
+    /// See this prop on [`AnimatedFor`].
+    #[prop(default = false)]
+    handle_margins: bool,
+
+    /// See this prop on [`AnimatedFor`].
+    #[prop(default = FadeAnimation::default().into(), into)]
+    enter_anim: AnyEnterAnimation,
+
+    /// See this prop on [`AnimatedFor`].
+    #[prop(default = FadeAnimation::default().into(), into)]
+    leave_anim: AnyLeaveAnimation,
+
+    /// The router scrolls the window (usually to the top) when navigating, which would make the
+    /// leaving page jump. If enabled, the leaving page is moved by the scrolled distance so it
+    /// stays in place visually while it animates out.
+    #[prop(default = true)]
+    compensate_window_scroll: bool,
+) -> impl IntoView {
+    let route = use_route();
+
+    let each = move || route.child();
+
+    let key = |child: &RouteContext| child.path();
+
+    let children = |child: &RouteContext| {
+        provide_context(child.clone());
+        child.outlet()
+    };
+
+    // The scroll listeners of the leaving pages and the timeouts that remove them, so that they
+    // can also be removed when the outlet is unmounted first.
+    let scroll_listeners =
+        StoredValue::new(HashMap::<usize, (WindowListenerHandle, TimeoutHandle)>::new());
+    let next_listener_id = StoredValue::new(0usize);
+
+    on_cleanup(move || {
+        if let Some(listeners) = scroll_listeners.try_update_value(std::mem::take) {
+            for (listener, timeout) in listeners.into_values() {
+                listener.remove();
+                timeout.clear();
+            }
+        }
+    });
+
+    let on_leave_start = Callback::new(move |(el, position): (web_sys::Element, Position)| {
+        if !compensate_window_scroll {
+            return;
+        }
+
+        // The pages are rendered by the routes, so they are HTML elements.
+        let Ok(el) = el.dyn_into::<web_sys::HtmlElement>() else {
+            return;
+        };
+
+        let scroll_y = window().scroll_y().unwrap_or_default();
+
+        let listener = window_event_listener(ev::scroll, move |_| {
+            let delta = window().scroll_y().unwrap_or_default() - scroll_y;
+            el.style()
+                .set_property("top", &format!("{}px", position.y + delta))
+                .unwrap();
+        });
+
+        let id = next_listener_id.get_value();
+        next_listener_id.set_value(id + 1);
+
+        let timeout = set_timeout_with_handle(
+            move || {
+                if let Some(Some((listener, _))) =
+                    scroll_listeners.try_update_value(|listeners| listeners.remove(&id))
+                {
+                    listener.remove();
+                }
+            },
+            SCROLL_COMPENSATION_TIMEOUT,
+        );
+
+        match timeout {
+            Ok(timeout) => scroll_listeners.update_value(|listeners| {
+                listeners.insert(id, (listener, timeout));
+            }),
+            // Without the timeout, nothing would remove the listener later.
+            Err(_) => listener.remove(),
+        }
+    });
+
+    view! {
+        <AnimatedFor
+            each
+            key
+            children
+            appear
+            animate_size=true
+            enter_anim
+            leave_anim
+            handle_margins
+            on_leave_start
+        />
+    }
+}
@@ -1,5 +1,9 @@
+use leptos::leptos_dom::is_server;
 use leptos::*;
+use wasm_bindgen::closure::Closure;
+use web_sys::Animation;
 
+use crate::animated_for::{extract_el_from_view, find_first_element, with_server_style};
 use crate::{AnimatedFor, AnyEnterAnimation, AnyLeaveAnimation, FadeAnimation};
 
 /// Animated version of [`<Show />`][leptos::Show] without the fallback.
@@ -33,7 +37,39 @@ pub fn AnimatedShow(
     /// See this prop on [`AnimatedFor`].
     #[prop(default = false)]
     handle_margins: bool,
+
+    /// See this prop on [`AnimatedFor`].
+    #[prop(default = false)]
+    keep_in_flow: bool,
+
+    /// Keep the child mounted while it is hidden and hide it using `display: none` instead.
+    /// This preserves the state of the child, for example the contents of a form or the playback
+    /// position of a video.
+    ///
+    /// The child is created once and must render to an element. `handle_margins` has no effect in
+    /// this mode, since the element stays in the layout until its leave animation has finished.
+    #[prop(default = false)]
+    keep_mounted: bool,
+
+    /// Callback that is called when the enter animation has finished, for example to focus an
+    /// input once a panel is open. It is not called for the initial render unless `appear` is set.
+    ///
+    /// If `when` changes before the animation has finished, the animation is interrupted and the
+    /// callback is not called. Only the callback of the last transition fires.
+    #[prop(optional)]
+    on_shown: Option<Callback<()>>,
+
+    /// Callback that is called when the leave animation has finished and the child has been
+    /// hidden. See `on_shown` for the behavior on interruptions.
+    #[prop(optional)]
+    on_hidden: Option<Callback<()>>,
 ) -> impl IntoView {
+    if keep_mounted {
+        return kept_mounted_view(
+            children, when, enter_anim, leave_anim, appear, on_shown, on_hidden,
+        );
+    }
+
     let each = move || {
         if when.get() {
             vec![()]
@@ -44,9 +80,110 @@ pub fn AnimatedShow(
 
     let children_fn = move |_d: &()| children();
 
+    let on_enter_end = Callback::new(move |_| {
+        if let Some(on_shown) = on_shown {
+            on_shown(());
+        }
+    });
+
+    let on_leave_end = Callback::new(move |_| {
+        if let Some(on_hidden) = on_hidden {
+            on_hidden(());
+        }
+    });
+
     view! {
         <AnimatedFor each key=|_| 0 children=children_fn
-            appear enter_anim leave_anim handle_margins
+            appear enter_anim leave_anim handle_margins keep_in_flow on_enter_end on_leave_end
         />
     }
+    .into_view()
+}
+
+/// Implementation of [`AnimatedShow`] with `keep_mounted`. The child is rendered once and shown or
+/// hidden by toggling its `display` property. The leave animation runs before `display: none` is
+/// applied.
+fn kept_mounted_view(
+    children: ChildrenFn,
+    when: Signal<bool>,
+    enter_anim: AnyEnterAnimation,
+    leave_anim: AnyLeaveAnimation,
+    appear: bool,
+    on_shown: Option<Callback<()>>,
+    on_hidden: Option<Callback<()>>,
+) -> View {
+    let view = children().into_view();
+
+    // The client only hides the element once it has hydrated, so the server needs to hide it
+    // already to keep it from flashing.
+    if is_server() {
+        if !when.get_untracked() {
+            return with_server_style(view, "display", "none");
+        }
+        return view;
+    }
+
+    let Some(el) = extract_el_from_view(&view)
+        .ok()
+        .or_else(|| find_first_element(&view))
+    else {
+        logging::warn!("AnimatedShow with keep_mounted requires the child to be an element");
+        return view;
+    };
+
+    let cur_anim: StoredValue<Option<Animation>> = store_value(None);
+
+    create_render_effect(move |prev: Option<bool>| {
+        let shown = when.get();
+
+        if prev == Some(shown) {
+            return shown;
+        }
+
+        // Interrupt a running enter or leave animation. Cancelled animations don't call their
+        // `onfinish` handler, so an interrupted leave animation won't hide the element.
+        cur_anim.update_value(|anim| {
+            if let Some(anim) = anim.take() {
+                anim.cancel();
+            }
+        });
+
+        if shown {
+            el.style().remove_property("display").unwrap();
+
+            if prev.is_some() || appear {
+                let anim = enter_anim.animate(&el);
+
+                if let Some(on_shown) = on_shown {
+                    let closure = Closure::<dyn Fn(web_sys::Event)>::new(move |_| on_shown(()))
+                        .into_js_value();
+                    anim.set_onfinish(Some(&closure.into()));
+                }
+
+                cur_anim.set_value(Some(anim));
+            }
+        } else if prev.is_none() {
+            el.style().set_property("display", "none").unwrap();
+        } else {
+            let (anim, _) = leave_anim.animate(&el);
+
+            let closure = Closure::<dyn Fn(web_sys::Event)>::new({
+                let el = el.clone();
+                move |_| {
+                    el.style().set_property("display", "none").unwrap();
+                    if let Some(on_hidden) = on_hidden {
+                        on_hidden(());
+                    }
+                }
+            })
+            .into_js_value();
+
+            anim.set_onfinish(Some(&closure.into()));
+            cur_anim.set_value(Some(anim));
+        }
+
+        shown
+    });
+
+    view
 }
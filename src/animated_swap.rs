@@ -1,12 +1,40 @@
 use crate::{AnimatedFor, AnyEnterAnimation, AnyLeaveAnimation, FadeAnimation};
+use leptos::leptos_dom::is_server;
 use leptos::*;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::js_sys;
 
 /// Animated transition between views.
+///
+/// For route-like transitions that should animate in the opposite direction when going back, use
+/// a [`SlideAnimation`][crate::SlideAnimation] with a direction signal as the enter and leave
+/// animation.
+///
+/// By default, every change of `content` swaps the views, even if the new view is logically the
+/// same. Set `swap_key` to only swap when it changes:
+///
+/// ```
+/// let content = Signal::derive(move || match tab.get() {
+///     Tab::Profile => view! { <Profile user=user.get() /> }.into_view(),
+///     Tab::Settings => view! { <Settings /> }.into_view(),
+/// });
+///
+/// view! {
+///     // Changing the user replaces the profile in place, only changing the tab animates.
+///     <AnimatedSwap content swap_key=Signal::derive(move || tab.get().to_string()) />
+/// }
+/// ```
 #[component]
 pub fn AnimatedSwap(
     /// The view to show.
     content: Signal<View>,
 
+    /// Key of the content. If set, the views are only swapped when the key changes; changes of
+    /// `content` with the same key replace the current view without an animation.
+    #[prop(optional, into)]
+    swap_key: Option<Signal<String>>,
+
     /// See this prop on [`AnimatedFor`].
     #[prop(default = false)]
     appear: bool,
@@ -22,12 +50,43 @@ pub fn AnimatedSwap(
     /// See this prop on [`AnimatedFor`].
     #[prop(default = FadeAnimation::default().into(), into)]
     leave_anim: AnyLeaveAnimation,
+
+    /// Use the browser's native
+    /// [View Transitions API](https://developer.mozilla.org/en-US/docs/Web/API/View_Transitions_API)
+    /// if it is available, and fall back to the regular animations otherwise.
+    ///
+    /// With view transitions, `enter_anim`, `leave_anim` and `appear` are bypassed. The browser
+    /// cross-fades a snapshot of the old content into the new one, which can be customized in CSS
+    /// using the `::view-transition-old(...)` and `::view-transition-new(...)` pseudo-elements
+    /// (for the `root` or for elements with a `view-transition-name`).
+    ///
+    /// Support is detected on the client, so the server can't know which markup to render. Don't
+    /// use this together with hydration.
+    #[prop(default = false)]
+    use_view_transition: bool,
 ) -> impl IntoView {
+    if use_view_transition && supports_view_transitions() {
+        return view_transition_swap(content, swap_key);
+    }
+
     let key = StoredValue::new(0);
+    let last_swap_key = StoredValue::new(None::<String>);
 
     let element = Memo::new(move |_| {
-        let k = (key.get_value() + 1) % 100;
-        key.set_value(k);
+        let swap = match swap_key {
+            Some(swap_key) => {
+                let swap_key = Some(swap_key.get());
+                let changed = last_swap_key.with_value(|last| *last != swap_key);
+                last_swap_key.set_value(swap_key);
+                changed
+            }
+            None => true,
+        };
+
+        if swap {
+            let k = (key.get_value() + 1) % 100;
+            key.set_value(k);
+        }
         content.get()
     });
 
@@ -36,7 +95,17 @@ pub fn AnimatedSwap(
         [key.get_value()]
     };
 
-    let children_fn = move |_: &i32| element.get();
+    let children_fn = move |k: &i32| {
+        let k = *k;
+
+        // Follows the content while it has the same key, and keeps the last view while leaving.
+        let view = Memo::new(move |prev: Option<&View>| match prev {
+            Some(prev) if key.get_value() != k => prev.clone(),
+            _ => element.get(),
+        });
+
+        move || view.get()
+    };
 
     view! {
         <AnimatedFor
@@ -50,4 +119,48 @@ pub fn AnimatedSwap(
             handle_margins
         />
     }
+    .into_view()
+}
+
+/// Whether the browser supports `document.startViewTransition`.
+pub(crate) fn supports_view_transitions() -> bool {
+    !is_server()
+        && js_sys::Reflect::has(&document(), &"startViewTransition".into()).unwrap_or(false)
+}
+
+/// Implementation of [`AnimatedSwap`] using the View Transitions API. The content is swapped
+/// inside the update callback of the view transition. Changes with the same `swap_key` replace
+/// the content directly, without a transition.
+fn view_transition_swap(content: Signal<View>, swap_key: Option<Signal<String>>) -> View {
+    let shown = RwSignal::new(content.get_untracked());
+
+    create_effect(move |prev: Option<Option<String>>| {
+        let view = content.get();
+        let key = swap_key.map(|swap_key| swap_key.get());
+
+        let Some(prev_key) = prev else {
+            return key;
+        };
+
+        if swap_key.is_some() && prev_key == key {
+            shown.set(view);
+            return key;
+        }
+
+        let update = Closure::once_into_js({
+            let view = view.clone();
+            move || shown.set(view)
+        });
+
+        if document()
+            .start_view_transition_with_update_callback(Some(update.unchecked_ref()))
+            .is_err()
+        {
+            shown.set(view);
+        }
+
+        key
+    });
+
+    (move || shown.get()).into_view()
 }
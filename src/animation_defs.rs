@@ -1,45 +1,284 @@
-use crate::{dynamics::SecondOrderDynamics, ElementSnapshot, Extent};
+use crate::animate::keyframe_to_js;
+use crate::animated_for::{EnterAnimationHandler, LeaveAnimationHandler};
+use crate::{
+    animate, dynamics::SecondOrderDynamics, AnimateOptions, ElementSnapshot, Extent, Position,
+};
 use itertools::Itertools;
-use leptos::{logging, Oco};
+use leptos::{logging, MaybeSignal, Oco, SignalGetUntracked};
 use std::time::Duration;
+use wasm_bindgen::JsCast;
+use web_sys::js_sys::Array;
 
-/// Return value for any enter/leave animation.
-pub struct AnimationConfig<T: serde::Serialize> {
+/// Duration and timing function of an animation, shared by all animation configs.
+#[derive(Clone, Debug)]
+pub struct Timing {
     /// Duration of the animation
     pub duration: Duration,
 
     /// Timing function of the animation (passed as the [`easing` parameter](https://developer.mozilla.org/en-US/docs/Web/API/KeyframeEffect/KeyframeEffect#easing) to JS)
     pub timing_fn: Option<Oco<'static, str>>,
 
+    /// Time to wait before the animation starts.
+    pub delay: Duration,
+}
+
+impl Timing {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            timing_fn: None,
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub fn timing_fn<TF: Into<Oco<'static, str>>>(mut self, timing_fn: TF) -> Self {
+        self.timing_fn = Some(timing_fn.into());
+        self
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Limit the duration to the range from `min` to `max`. Panics if `min > max`.
+    pub fn clamp_duration(mut self, min: Duration, max: Duration) -> Self {
+        self.duration = self.duration.clamp(min, max);
+        self
+    }
+
+    /// The options for [`animate`][crate::animate] to run an animation with this timing.
+    pub fn to_options(&self) -> AnimateOptions {
+        AnimateOptions {
+            easing: self.timing_fn.clone(),
+            delay: self.delay,
+            ..AnimateOptions::new(self.duration)
+        }
+    }
+}
+
+/// Return value for any enter/leave animation.
+#[derive(Clone, Debug)]
+pub struct AnimationConfig<T: serde::Serialize> {
+    /// Duration, timing function and delay of the animation.
+    pub timing: Timing,
+
     /// Keyframes. Ensure that `T` uses `#[serde(rename_all = "camelCase")]`
     pub keyframes: Vec<T>,
+
+    /// Animate a pseudo-element such as `::before` or `::after` of the element instead of the
+    /// element itself. This allows for decorative effects like overlays without extra DOM nodes.
+    ///
+    /// Passed as the [`pseudoElement` option](https://developer.mozilla.org/en-US/docs/Web/API/Element/animate#pseudoelement)
+    /// to JS. Older browsers ignore it and animate the element instead, see MDN for the browser
+    /// support.
+    pub pseudo_element: Option<Oco<'static, str>>,
+
+    /// Whether to start from the element's current style instead of the first keyframe, which is
+    /// then left out. This makes an animation that interrupts another one (for example a leave
+    /// that interrupts an enter) continue from where the element currently is instead of jumping
+    /// to the first keyframe.
+    ///
+    /// For a leave-animation that interrupts an enter-animation in
+    /// [`AnimatedFor`][crate::AnimatedFor], the current values of the properties in the keyframes
+    /// are kept, except for transforms, which may include the translation of a move.
+    pub from_current: bool,
+}
+
+impl<T: serde::Serialize> AnimationConfig<T> {
+    /// An animation through the `keyframes`, without a pseudo-element and starting from the first
+    /// keyframe.
+    pub fn new(timing: Timing, keyframes: Vec<T>) -> Self {
+        Self {
+            timing,
+            keyframes,
+            pseudo_element: None,
+            from_current: false,
+        }
+    }
+
+    pub fn pseudo_element<PE: Into<Oco<'static, str>>>(mut self, pseudo_element: PE) -> Self {
+        self.pseudo_element = Some(pseudo_element.into());
+        self
+    }
+
+    pub fn from_current(mut self, from_current: bool) -> Self {
+        self.from_current = from_current;
+        self
+    }
+}
+
+/// Wrapper for keyframes of an [`EnterAnimation`] or [`LeaveAnimation`] that adds a keyframe-level
+/// easing and offset to the props.
+///
+/// The `easing` of a keyframe applies to the segment from this keyframe to the next one, on top of
+/// the overall `timing_fn` of the animation (which maps the time to the progress of the whole
+/// animation). This allows, for example, a quick fade-in at the start followed by a slow slide in
+/// a single animation. Note that the easing applies to all properties of the segment; properties
+/// that need entirely different curves must be animated in separate keyframes (using `offset`)
+/// or separate animations.
+///
+/// # Example
+/// ```
+/// keyframes: vec![
+///     Keyframe::new(MyProps { opacity: 0.0, transform: "translateY(20px)" }).easing("ease-out"),
+///     Keyframe::new(MyProps { opacity: 1.0, transform: "translateY(10px)" }).offset(0.3).easing("ease-in-out"),
+///     Keyframe::new(MyProps { opacity: 1.0, transform: "none" }),
+/// ],
+/// ```
+#[derive(serde::Serialize)]
+pub struct Keyframe<T: serde::Serialize> {
+    #[serde(flatten)]
+    pub props: T,
+
+    /// Easing from this keyframe to the next one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub easing: Option<String>,
+
+    /// Position of the keyframe in the animation, from 0 to 1. Spaced evenly if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<f64>,
+}
+
+impl<T: serde::Serialize> Keyframe<T> {
+    pub fn new(props: T) -> Self {
+        Self {
+            props,
+            easing: None,
+            offset: None,
+        }
+    }
+
+    pub fn easing<E: Into<String>>(mut self, easing: E) -> Self {
+        self.easing = Some(easing.into());
+        self
+    }
+
+    pub fn offset(mut self, offset: f64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
 }
 
 /// Return value for any move animation.
+#[derive(Clone, Debug)]
 pub struct AnimationConfigMove {
-    /// Duration of the animation
-    pub duration: Duration,
+    /// Duration, timing function and delay of the animation.
+    pub timing: Timing,
 
-    /// Timing function of the animation (passed as the [`easing` parameter](https://developer.mozilla.org/en-US/docs/Web/API/KeyframeEffect/KeyframeEffect#easing) to JS)
-    pub timing_fn: Option<Oco<'static, str>>,
+    /// Positions that the element passes through between `from` and `to`, for moves that don't
+    /// follow a straight line. Leave empty for a straight move.
+    pub waypoints: Vec<MoveWaypoint>,
+
+    /// Separate timing for the vertical part of the move, for example to move the element
+    /// sideways first and then down like a staircase. `timing` is then only used for the
+    /// horizontal part.
+    ///
+    /// The two axes run as two animations that are combined. The size change and the waypoints
+    /// go with the axis that ends last, whose animation is also the one that
+    /// [`AnimatedFor`][crate::AnimatedFor] tracks, for example for `on_transition_end`. If the
+    /// element only moves along one axis, a single animation with the timing of that axis is used
+    /// instead.
+    pub vertical_timing: Option<Timing>,
+
+    /// The `transform-origin` at the start and the end of the move, and at the waypoints that
+    /// don't set their own. Defaults to `top left`.
+    ///
+    /// The origin only affects the rotation of the waypoints and the scale of `scale_size`, not
+    /// the translation. The scale is calculated for `top left`, so keep the default with
+    /// `scale_size`.
+    pub transform_origin: Option<Oco<'static, str>>,
+}
+
+impl AnimationConfigMove {
+    /// A straight move with the same timing for both axes.
+    pub fn new(timing: Timing) -> Self {
+        Self {
+            timing,
+            waypoints: Vec::new(),
+            vertical_timing: None,
+            transform_origin: None,
+        }
+    }
+
+    pub fn waypoints(mut self, waypoints: Vec<MoveWaypoint>) -> Self {
+        self.waypoints = waypoints;
+        self
+    }
+
+    pub fn vertical_timing(mut self, vertical_timing: Timing) -> Self {
+        self.vertical_timing = Some(vertical_timing);
+        self
+    }
+
+    pub fn transform_origin<T: Into<Oco<'static, str>>>(mut self, transform_origin: T) -> Self {
+        self.transform_origin = Some(transform_origin.into());
+        self
+    }
+}
+
+/// An intermediate position of a move animation, see [`AnimationConfigMove::waypoints`].
+#[derive(Clone, Debug)]
+pub struct MoveWaypoint {
+    /// The progress of the animation at which the element is at `position`, from 0 to 1.
+    pub offset: f64,
+
+    /// The position of the element, in the same coordinates as the snapshots.
+    pub position: Position,
+
+    /// Rotation of the element at this waypoint, in degrees, around its `transform_origin`. The
+    /// element isn't rotated at the start and the end of the move.
+    pub rotation: f64,
+
+    /// The `transform-origin` at this waypoint. The origin is interpolated between the
+    /// keyframes, so the pivot of the rotation can shift during the move. Uses the one of the
+    /// [`AnimationConfigMove`] if unset.
+    pub transform_origin: Option<Oco<'static, str>>,
+}
+
+impl MoveWaypoint {
+    pub fn new(offset: f64, position: Position) -> Self {
+        Self {
+            offset,
+            position,
+            rotation: 0.0,
+            transform_origin: None,
+        }
+    }
+
+    pub fn rotation(mut self, rotation: f64) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn transform_origin<T: Into<Oco<'static, str>>>(mut self, transform_origin: T) -> Self {
+        self.transform_origin = Some(transform_origin.into());
+        self
+    }
 }
 
 /// Return value for any resize animation - currently only used in [`SizeTransition`][crate::SizeTransition].
+#[derive(Clone, Debug)]
 pub struct AnimationConfigResize {
-    /// Duration of the animation
-    pub duration: Duration,
-
-    /// Timing function of the animation (passed as the [`easing` parameter](https://developer.mozilla.org/en-US/docs/Web/API/KeyframeEffect/KeyframeEffect#easing) to JS)
-    pub timing_fn: Option<Oco<'static, str>>,
+    /// Duration, timing function and delay of the animation.
+    pub timing: Timing,
 }
 
 /// Trait for defining an enter animation.
+///
+/// **Migration:** `enter` used to take no arguments. Implementations that don't need the element
+/// can simply ignore it: `fn enter(&self, _el: &web_sys::Element)`. It is an `Element` rather than
+/// an `HtmlElement`, since the items inside of an `<svg>` are SVG elements.
 pub trait EnterAnimation {
     /// The CSS properties on the keyframes.
     type Props: serde::Serialize;
 
     /// Generate the keyframes, timing function, duration, etc.
-    fn enter(&self) -> AnimationConfig<Self::Props>;
+    ///
+    /// Receives the entering element, which is already in its final place in the layout, so that
+    /// the animation can measure it, for example to expand from a height of 0 to its actual
+    /// height.
+    fn enter(&self, el: &web_sys::Element) -> AnimationConfig<Self::Props>;
 }
 
 /// Trait for defining a leave animation.
@@ -61,7 +300,8 @@ pub trait MoveAnimation {
     fn animate(&self, from: ElementSnapshot, to: ElementSnapshot) -> AnimationConfigMove;
 }
 
-/// Trait for defining a resize animation (currently only used in [`SizeTransition`][crate::SizeTransition]).
+/// Trait for defining a resize animation, used by [`SizeTransition`][crate::SizeTransition] and
+/// [`AnyResizeAnimation`][crate::AnyResizeAnimation].
 pub trait ResizeAnimation {
     /// Generate the timing function and duration. Currently does not support keyframes which makes
     /// the `from` and `to` parameters not very useful.
@@ -69,6 +309,10 @@ pub trait ResizeAnimation {
 }
 
 /// A simple enter / leave animation that fades the elements in and out using `opacity`
+///
+/// The leave-animation starts from the current opacity, so an element that is removed while it
+/// is still fading in fades out from where it is instead of jumping to full opacity first.
+#[derive(Debug)]
 pub struct FadeAnimation {
     pub timing_fn: Oco<'static, str>,
     pub duration: Duration,
@@ -101,17 +345,17 @@ pub struct FadeAnimationProps {
 impl EnterAnimation for FadeAnimation {
     type Props = FadeAnimationProps;
 
-    fn enter(&self) -> AnimationConfig<Self::Props> {
-        let duration = self.duration;
-        let timing_fn = Some(self.timing_fn.clone());
+    fn enter(&self, _el: &web_sys::Element) -> AnimationConfig<Self::Props> {
+        let timing = Timing::new(self.duration).timing_fn(self.timing_fn.clone());
 
         AnimationConfig {
-            duration,
-            timing_fn,
+            timing,
             keyframes: vec![
                 FadeAnimationProps { opacity: 0.0 },
                 FadeAnimationProps { opacity: 1.0 },
             ],
+            pseudo_element: None,
+            from_current: false,
         }
     }
 }
@@ -120,21 +364,144 @@ impl LeaveAnimation for FadeAnimation {
     type Props = FadeAnimationProps;
 
     fn leave(&self) -> AnimationConfig<Self::Props> {
-        let duration = self.duration;
-        let timing_fn = Some(self.timing_fn.clone());
+        let timing = Timing::new(self.duration).timing_fn(self.timing_fn.clone());
 
         AnimationConfig {
-            duration,
-            timing_fn,
+            timing,
             keyframes: vec![
                 FadeAnimationProps { opacity: 1.0 },
                 FadeAnimationProps { opacity: 0.0 },
             ],
+            pseudo_element: None,
+            from_current: true,
+        }
+    }
+}
+
+/// The direction of a transition, for example whether the user navigated forwards or backwards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TransitionDirection {
+    #[default]
+    Forward,
+    Back,
+}
+
+/// An enter / leave animation that slides the elements in and out by an offset while fading them.
+///
+/// When the direction is [`TransitionDirection::Forward`], entering elements slide in from
+/// `offset` and leaving elements slide out towards `-offset`, so with a positive x offset the
+/// content moves from right to left. For [`TransitionDirection::Back`] this is mirrored.
+///
+/// The direction can be a signal, which is read when an animation starts. This is useful for
+/// route transitions ([`AnimatedSwap`][crate::AnimatedSwap] or `AnimatedOutlet`), where back
+/// navigation should animate in the opposite direction. The crate can't infer the navigation
+/// direction by itself, so the signal must be updated *before* the content changes, for example in
+/// the click handler of a back button, or by comparing an index stored in `history.state` in a
+/// `popstate` listener.
+pub struct SlideAnimation {
+    pub timing_fn: Oco<'static, str>,
+    pub duration: Duration,
+
+    /// The offset in px that forward-entering elements start at.
+    pub offset: Position,
+
+    pub direction: MaybeSignal<TransitionDirection>,
+}
+
+impl SlideAnimation {
+    pub fn new<TF: Into<Oco<'static, str>>, O: Into<Position>>(
+        duration: Duration,
+        timing_fn: TF,
+        offset: O,
+    ) -> Self {
+        Self {
+            duration,
+            timing_fn: timing_fn.into(),
+            offset: offset.into(),
+            direction: MaybeSignal::Static(TransitionDirection::Forward),
+        }
+    }
+
+    /// Set the direction of the transition. See [`SlideAnimation`].
+    pub fn with_direction<D: Into<MaybeSignal<TransitionDirection>>>(
+        mut self,
+        direction: D,
+    ) -> Self {
+        self.direction = direction.into();
+        self
+    }
+
+    /// The offset for the current direction.
+    fn directed_offset(&self) -> Position {
+        match self.direction.get_untracked() {
+            TransitionDirection::Forward => self.offset,
+            TransitionDirection::Back => Position::default() - self.offset,
+        }
+    }
+}
+
+impl Default for SlideAnimation {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(200), "ease-out", (100.0, 0.0))
+    }
+}
+
+#[doc(hidden)]
+#[derive(serde::Serialize)]
+pub struct SlideAnimationProps {
+    opacity: f64,
+    transform: String,
+}
+
+impl SlideAnimationProps {
+    fn new(opacity: f64, offset: Position) -> Self {
+        Self {
+            opacity,
+            transform: format!("translate({}px, {}px)", offset.x, offset.y),
+        }
+    }
+}
+
+impl EnterAnimation for SlideAnimation {
+    type Props = SlideAnimationProps;
+
+    fn enter(&self, _el: &web_sys::Element) -> AnimationConfig<Self::Props> {
+        let timing = Timing::new(self.duration).timing_fn(self.timing_fn.clone());
+        let offset = self.directed_offset();
+
+        AnimationConfig {
+            timing,
+            keyframes: vec![
+                SlideAnimationProps::new(0.0, offset),
+                SlideAnimationProps::new(1.0, Position::default()),
+            ],
+            pseudo_element: None,
+            from_current: false,
+        }
+    }
+}
+
+impl LeaveAnimation for SlideAnimation {
+    type Props = SlideAnimationProps;
+
+    fn leave(&self) -> AnimationConfig<Self::Props> {
+        let timing = Timing::new(self.duration).timing_fn(self.timing_fn.clone());
+        let offset = self.directed_offset();
+
+        AnimationConfig {
+            timing,
+            keyframes: vec![
+                SlideAnimationProps::new(1.0, Position::default()),
+                SlideAnimationProps::new(0.0, Position::default() - offset),
+            ],
+            pseudo_element: None,
+            from_current: false,
         }
     }
 }
 
 /// A simple move / resize animation that changes the respective props based on the timing function.
+#[derive(Debug)]
 pub struct SlidingAnimation {
     pub timing_fn: Oco<'static, str>,
     pub duration: Duration,
@@ -160,25 +527,17 @@ impl SlidingAnimation {
 
 impl MoveAnimation for SlidingAnimation {
     fn animate(&self, _from: ElementSnapshot, _to: ElementSnapshot) -> AnimationConfigMove {
-        let duration = self.duration;
-        let timing_fn = Some(self.timing_fn.clone());
+        let timing = Timing::new(self.duration).timing_fn(self.timing_fn.clone());
 
-        AnimationConfigMove {
-            duration,
-            timing_fn,
-        }
+        AnimationConfigMove::new(timing)
     }
 }
 
 impl ResizeAnimation for SlidingAnimation {
     fn animate(&self, _from: Extent, _to: Extent) -> AnimationConfigResize {
-        let duration = self.duration;
-        let timing_fn = Some(self.timing_fn.clone());
+        let timing = Timing::new(self.duration).timing_fn(self.timing_fn.clone());
 
-        AnimationConfigResize {
-            duration,
-            timing_fn,
-        }
+        AnimationConfigResize { timing }
     }
 }
 
@@ -188,9 +547,11 @@ fn fuzzy_compare(a: f64, b: f64) -> bool {
 }
 
 /// A move / resize animation using a simulation of [second order dynamics](https://www.youtube.com/watch?v=KPoeNZZ6H4s).
+#[derive(Debug)]
 pub struct DynamicsAnimation {
     timing_fn: Oco<'static, str>,
     duration: Duration,
+    samples: Vec<f64>,
 }
 
 impl DynamicsAnimation {
@@ -203,10 +564,8 @@ impl DynamicsAnimation {
         let mut dynamics = SecondOrderDynamics::new(f, z, r, 0.0);
         let mut data = vec![];
 
-        const ITERATION_RATE: f32 = 15.0;
-
         loop {
-            dynamics.update(1.0, 1.0 / ITERATION_RATE);
+            dynamics.update(1.0, 1.0 / Self::SAMPLE_RATE);
             data.push(dynamics.get());
             if data.len() > 1000 {
                 logging::error!("DynamicsAnimation too long!");
@@ -218,35 +577,982 @@ impl DynamicsAnimation {
             }
         }
 
-        let duration = Duration::from_secs_f32(data.len() as f32 / ITERATION_RATE);
+        let duration = Duration::from_secs_f32(data.len() as f32 / Self::SAMPLE_RATE);
 
         Self {
             duration,
             timing_fn: Oco::Owned(format!("linear({})", data.iter().join(", "))),
+            samples: data,
         }
     }
+
+    /// How many samples of the simulation are taken per second.
+    pub const SAMPLE_RATE: f32 = 15.0;
+
+    /// The simulated response curve that is used as the timing function, going from 0 towards 1.
+    /// Samples are spaced evenly in time at [`Self::SAMPLE_RATE`], so they can be used to
+    /// visualize the animation.
+    pub fn samples(&self) -> &[f64] {
+        &self.samples
+    }
+
+    /// The duration of the animation.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
 }
 
 impl MoveAnimation for DynamicsAnimation {
     fn animate(&self, _from: ElementSnapshot, _to: ElementSnapshot) -> AnimationConfigMove {
-        let duration = self.duration;
-        let timing_fn = Some(self.timing_fn.clone());
+        let timing = Timing::new(self.duration).timing_fn(self.timing_fn.clone());
 
-        AnimationConfigMove {
-            duration,
-            timing_fn,
-        }
+        AnimationConfigMove::new(timing)
     }
 }
 
 impl ResizeAnimation for DynamicsAnimation {
     fn animate(&self, _from: Extent, _to: Extent) -> AnimationConfigResize {
-        let duration = self.duration;
-        let timing_fn = Some(self.timing_fn.clone());
+        let timing = Timing::new(self.duration).timing_fn(self.timing_fn.clone());
+
+        AnimationConfigResize { timing }
+    }
+}
+
+/// Sample a CSS `cubic-bezier(x1, y1, x2, y2)` timing function at `count` evenly spaced points
+/// in time, from 0 to 1 (inclusive). The result has the same form as
+/// [`DynamicsAnimation::samples`], so both can be compared, mixed or turned into a CSS `linear()`
+/// function.
+///
+/// `x1` and `x2` must be between 0 and 1, just like in CSS. `count` is at least 2.
+///
+/// # Example
+/// ```
+/// // `ease-out`
+/// let samples = sample_cubic_bezier(0.0, 0.0, 0.58, 1.0, 20);
+/// let timing_fn = samples.into_iter().collect::<LinearEasing>();
+/// ```
+pub fn sample_cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, count: usize) -> Vec<f64> {
+    // The bezier curve for one dimension, with the end points fixed at 0 and 1.
+    let bezier = |p1: f64, p2: f64, t: f64| {
+        let u = 1.0 - t;
+        3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t
+    };
+    let derivative = |p1: f64, p2: f64, t: f64| {
+        let u = 1.0 - t;
+        3.0 * u * u * p1 + 6.0 * u * t * (p2 - p1) + 3.0 * t * t * (1.0 - p2)
+    };
+
+    // Find the curve parameter at which the curve reaches the time `x`. Newton's method
+    // converges quickly in most cases, bisection catches the flat parts of the curve.
+    let solve_t = |x: f64| {
+        let mut t = x;
+        for _ in 0..8 {
+            let error = bezier(x1, x2, t) - x;
+            if error.abs() < 1e-7 {
+                return t;
+            }
+            let slope = derivative(x1, x2, t);
+            if slope.abs() < 1e-6 {
+                break;
+            }
+            t = (t - error / slope).clamp(0.0, 1.0);
+        }
+
+        let (mut low, mut high) = (0.0, 1.0);
+        t = x;
+        for _ in 0..50 {
+            let value = bezier(x1, x2, t);
+            if (value - x).abs() < 1e-7 {
+                break;
+            }
+            if value < x {
+                low = t;
+            } else {
+                high = t;
+            }
+            t = (low + high) / 2.0;
+        }
+        t
+    };
+
+    let count = count.max(2);
+    (0..count)
+        .map(|i| {
+            let x = i as f64 / (count - 1) as f64;
+            bezier(y1, y2, solve_t(x))
+        })
+        .collect()
+}
+
+/// Builder for a CSS `linear()` timing function with explicit input offsets, such as
+/// `linear(0, 0.5 25%, 1)`. Points without an offset are spread evenly between their neighbours,
+/// just like in CSS. The builder converts into the `timing_fn` of a [`Timing`] or of the
+/// animations that take one.
+///
+/// # Example
+/// ```
+/// // Quickly move halfway, hold, then finish.
+/// let easing = LinearEasing::new()
+///     .point(0.0)
+///     .point_at(0.5, 25.0)
+///     .point_at(0.5, 75.0)
+///     .point(1.0);
+///
+/// let anim = FadeAnimation::new(Duration::from_millis(400), easing);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LinearEasing {
+    points: Vec<(f64, Option<f64>)>,
+}
+
+impl LinearEasing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a point with the output progress `value`, placed evenly between its neighbours.
+    pub fn point(mut self, value: f64) -> Self {
+        self.points.push((value, None));
+        self
+    }
+
+    /// Add a point with the output progress `value` at `offset` percent of the animation.
+    ///
+    /// Panics if `offset` is not between 0 and 100, or if it is before the offset of an earlier
+    /// point. Two points at the same offset make the value jump.
+    pub fn point_at(mut self, value: f64, offset: f64) -> Self {
+        assert!(
+            (0.0..=100.0).contains(&offset),
+            "linear() offset {offset}% is not between 0% and 100%"
+        );
+        if let Some(prev) = self.points.iter().rev().find_map(|(_, offset)| *offset) {
+            assert!(
+                offset >= prev,
+                "linear() offset {offset}% is before the previous offset {prev}%"
+            );
+        }
+
+        self.points.push((value, Some(offset)));
+        self
+    }
+
+    /// The CSS `linear()` function. `linear()` needs at least 2 points, so with fewer points this
+    /// falls back to the `linear` keyword instead.
+    pub fn to_css(&self) -> String {
+        if self.points.len() < 2 {
+            return "linear".to_string();
+        }
+
+        let stops = self.points.iter().map(|(value, offset)| match offset {
+            Some(offset) => format!("{value} {offset}%"),
+            None => value.to_string(),
+        });
+
+        format!("linear({})", stops.join(", "))
+    }
+}
+
+/// Collects evenly spaced points, for example the ones from [`sample_cubic_bezier`].
+impl FromIterator<f64> for LinearEasing {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        Self {
+            points: iter.into_iter().map(|value| (value, None)).collect(),
+        }
+    }
+}
+
+impl From<LinearEasing> for Oco<'static, str> {
+    fn from(easing: LinearEasing) -> Self {
+        Oco::Owned(easing.to_css())
+    }
+}
 
-        AnimationConfigResize {
+/// An enter / leave animation for accordions and other collapsible sections that animates the
+/// height of the element from 0 to its content height and back.
+///
+/// CSS can't animate to `height: auto`, so the height of the element is measured when the
+/// animation starts and animated to / from that fixed value. Since the animation doesn't fill,
+/// the element returns to its natural height once it has finished.
+///
+/// Use it together with `keep_in_flow` so that the following elements shift along while the
+/// section collapses. Move animations of other items in the same [`AnimatedFor`][crate::AnimatedFor]
+/// would fight with the changing height, so this works best for a single element, for example
+/// in an [`AnimatedShow`][crate::AnimatedShow]:
+///
+/// ```
+/// let anim = ExpandCollapseAnimation::new(Duration::from_millis(250), "ease-in-out");
+///
+/// view! {
+///     <AnimatedShow when=open enter_anim=anim.clone() leave_anim=anim keep_in_flow=true>
+///         <div class="section-contents">"..."</div>
+///     </AnimatedShow>
+/// }
+/// ```
+#[derive(Clone)]
+pub struct ExpandCollapseAnimation {
+    pub timing_fn: Oco<'static, str>,
+    pub duration: Duration,
+}
+
+impl ExpandCollapseAnimation {
+    pub fn new<T: Into<Oco<'static, str>>>(duration: Duration, timing_fn: T) -> Self {
+        Self {
             duration,
-            timing_fn,
+            timing_fn: timing_fn.into(),
+        }
+    }
+
+    /// Animate the height of the element between 0 and its current height.
+    fn animate_height(
+        &self,
+        el: &web_sys::HtmlElement,
+        expand: bool,
+    ) -> (web_sys::Animation, AnimateOptions) {
+        let height = el.get_bounding_client_rect().height();
+
+        let keyframe = |height: f64| keyframe_to_js(&ExpandCollapseKeyframe::new(height, None));
+
+        let (from, to) = if expand { (0.0, height) } else { (height, 0.0) };
+        let keyframes: Array = [keyframe(from), keyframe(to)].into_iter().collect();
+
+        let options = AnimateOptions::new(self.duration).easing(self.timing_fn.clone());
+
+        (animate(el, Some(&keyframes.into()), &options), options)
+    }
+}
+
+impl Default for ExpandCollapseAnimation {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(200), "ease-out")
+    }
+}
+
+#[doc(hidden)]
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpandCollapseKeyframe {
+    height: String,
+    box_sizing: &'static str,
+    overflow: &'static str,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    opacity: Option<f64>,
+}
+
+impl ExpandCollapseKeyframe {
+    fn new(height: f64, opacity: Option<f64>) -> Self {
+        Self {
+            height: format!("{height}px"),
+            box_sizing: "border-box",
+            overflow: "hidden",
+            opacity,
         }
     }
 }
+
+// The leave-animation needs to measure the element, which `LeaveAnimation` can't, so this
+// implements the handlers directly instead.
+impl EnterAnimationHandler for ExpandCollapseAnimation {
+    fn animate(&self, el: &web_sys::HtmlElement) -> web_sys::Animation {
+        self.animate_height(el, true).0
+    }
+}
+
+impl LeaveAnimationHandler for ExpandCollapseAnimation {
+    fn animate(&self, el: &web_sys::HtmlElement) -> (web_sys::Animation, AnimateOptions) {
+        self.animate_height(el, false)
+    }
+}
+
+/// An enter animation for feeds and chats, where new items expand from a height of 0 while they
+/// fade in, pushing the following items down as they grow.
+///
+/// The growing height already moves the following items along with the same timing, so
+/// [`AnimatedFor`][crate::AnimatedFor] measures the goals of their move-animations while the new
+/// items are still at a height of 0. The following items then only get a move-animation if they
+/// also moved for another reason, such as a reorder. Together with leaving items that collapse in
+/// place:
+///
+/// ```
+/// view! {
+///     <AnimatedFor
+///         each
+///         key
+///         children
+///         enter_anim=ExpandEnterAnimation::default()
+///         leave_anim=ExpandCollapseAnimation::default()
+///         keep_in_flow=true
+///     />
+/// }
+/// ```
+#[derive(Clone)]
+pub struct ExpandEnterAnimation {
+    pub timing_fn: Oco<'static, str>,
+    pub duration: Duration,
+}
+
+impl ExpandEnterAnimation {
+    pub fn new<T: Into<Oco<'static, str>>>(duration: Duration, timing_fn: T) -> Self {
+        Self {
+            duration,
+            timing_fn: timing_fn.into(),
+        }
+    }
+}
+
+impl Default for ExpandEnterAnimation {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(250), "ease-out")
+    }
+}
+
+impl EnterAnimation for ExpandEnterAnimation {
+    type Props = ExpandCollapseKeyframe;
+
+    fn enter(&self, el: &web_sys::Element) -> AnimationConfig<Self::Props> {
+        let height = el.get_bounding_client_rect().height();
+
+        AnimationConfig {
+            timing: Timing::new(self.duration).timing_fn(self.timing_fn.clone()),
+            keyframes: vec![
+                ExpandCollapseKeyframe::new(0.0, Some(0.0)),
+                ExpandCollapseKeyframe::new(height, Some(1.0)),
+            ],
+            pseudo_element: None,
+            from_current: false,
+        }
+    }
+}
+
+/// A move animation that follows a curved arc instead of a straight line, like the arced motion
+/// in Material Design.
+pub struct ArcMoveAnimation {
+    pub timing_fn: Oco<'static, str>,
+    pub duration: Duration,
+
+    /// How far the arc bends away from the straight line, relative to the length of the move.
+    /// Negative values bend it to the other side. Moves bend clockwise for positive values.
+    pub arc_height: f64,
+}
+
+impl ArcMoveAnimation {
+    pub fn new<T: Into<Oco<'static, str>>>(
+        duration: Duration,
+        timing_fn: T,
+        arc_height: f64,
+    ) -> Self {
+        Self {
+            duration,
+            timing_fn: timing_fn.into(),
+            arc_height,
+        }
+    }
+
+    /// Number of waypoints that approximate the arc.
+    const WAYPOINTS: usize = 8;
+}
+
+impl Default for ArcMoveAnimation {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(300), "ease-in-out", 0.25)
+    }
+}
+
+impl MoveAnimation for ArcMoveAnimation {
+    fn animate(&self, from: ElementSnapshot, to: ElementSnapshot) -> AnimationConfigMove {
+        let timing = Timing::new(self.duration).timing_fn(self.timing_fn.clone());
+
+        let (from, to) = (from.position, to.position);
+        let diff = to - from;
+
+        // Control point of a quadratic bezier curve, perpendicular to the middle of the move.
+        let control = Position {
+            x: (from.x + to.x) / 2.0 - diff.y * self.arc_height,
+            y: (from.y + to.y) / 2.0 + diff.x * self.arc_height,
+        };
+
+        let waypoints = (1..=Self::WAYPOINTS)
+            .map(|i| {
+                let t = i as f64 / (Self::WAYPOINTS + 1) as f64;
+                let position = Position::lerp(
+                    Position::lerp(from, control, t),
+                    Position::lerp(control, to, t),
+                    t,
+                );
+
+                MoveWaypoint::new(t, position)
+            })
+            .collect();
+
+        AnimationConfigMove::new(timing).waypoints(waypoints)
+    }
+}
+
+/// A move animation that moves the element along one axis first and then along the other, like
+/// walking down a staircase. Elements that only move along one axis just take a single step.
+/// Uses [`AnimationConfigMove::vertical_timing`].
+pub struct StaircaseMoveAnimation {
+    pub timing_fn: Oco<'static, str>,
+
+    /// Duration of each of the two steps.
+    pub step_duration: Duration,
+
+    /// Whether to move vertically first instead of horizontally.
+    pub vertical_first: bool,
+}
+
+impl StaircaseMoveAnimation {
+    pub fn new<T: Into<Oco<'static, str>>>(
+        step_duration: Duration,
+        timing_fn: T,
+        vertical_first: bool,
+    ) -> Self {
+        Self {
+            step_duration,
+            timing_fn: timing_fn.into(),
+            vertical_first,
+        }
+    }
+}
+
+impl Default for StaircaseMoveAnimation {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(200), "ease-in-out", false)
+    }
+}
+
+impl MoveAnimation for StaircaseMoveAnimation {
+    fn animate(&self, from: ElementSnapshot, to: ElementSnapshot) -> AnimationConfigMove {
+        let step = Timing::new(self.step_duration).timing_fn(self.timing_fn.clone());
+
+        // A single step doesn't need to wait for the other axis.
+        let moves_x = from.position.x != to.position.x;
+        let moves_y = from.position.y != to.position.y;
+        let second = if moves_x && moves_y {
+            step.clone().delay(self.step_duration)
+        } else {
+            step.clone()
+        };
+
+        let (timing, vertical_timing) = if self.vertical_first {
+            (second, step)
+        } else {
+            (step, second)
+        };
+
+        AnimationConfigMove::new(timing).vertical_timing(vertical_timing)
+    }
+}
+
+/// An attention animation that briefly flashes the background of an element in a color and then
+/// returns to its normal background, for example to highlight an item whose data was updated.
+///
+/// Run it using [`FlashAnimation::flash`] whenever the data of an item changes. It can also be
+/// used as the `enter_anim` of an [`AnimatedFor`][crate::AnimatedFor] to highlight new items.
+///
+/// # Example
+/// ```
+/// let flash = FlashAnimation::new("#fff3a0", Duration::from_millis(600));
+/// let el = NodeRef::<html::Div>::new();
+///
+/// create_effect(move |prev| {
+///     let price = price.get();
+///     if prev.is_some() {
+///         if let Some(el) = el.get_untracked() {
+///             flash.flash(&el);
+///         }
+///     }
+/// });
+/// ```
+#[derive(Clone)]
+pub struct FlashAnimation {
+    pub color: Oco<'static, str>,
+    pub timing_fn: Oco<'static, str>,
+    pub duration: Duration,
+}
+
+impl FlashAnimation {
+    pub fn new<C: Into<Oco<'static, str>>>(color: C, duration: Duration) -> Self {
+        Self {
+            color: color.into(),
+            timing_fn: Oco::Borrowed("ease-out"),
+            duration,
+        }
+    }
+
+    pub fn timing_fn<TF: Into<Oco<'static, str>>>(mut self, timing_fn: TF) -> Self {
+        self.timing_fn = timing_fn.into();
+        self
+    }
+
+    /// Flash the background of the element once. The flash peaks early and then fades back to the
+    /// background that the element currently has. A flash that is still running on the element is
+    /// cancelled, so that rapid updates restart the flash instead of stacking up.
+    pub fn flash(&self, el: &web_sys::HtmlElement) -> web_sys::Animation {
+        // A running flash would otherwise be read as the background below.
+        for anim in el.get_animations().iter() {
+            let anim: web_sys::Animation = anim.unchecked_into();
+            if anim.id() == FLASH_ANIMATION_ID {
+                anim.cancel();
+            }
+        }
+
+        // Read the current background so that the animation ends exactly where the element's own
+        // style continues once the animation is gone.
+        let background = leptos::window()
+            .get_computed_style(el)
+            .ok()
+            .flatten()
+            .and_then(|style| style.get_property_value("background-color").ok())
+            .unwrap_or_else(|| "transparent".to_string());
+
+        let keyframe = |background_color: &str, offset: f64| {
+            keyframe_to_js(&Keyframe::new(FlashKeyframe { background_color }).offset(offset))
+        };
+
+        let keyframes: Array = [
+            keyframe(background.as_str(), 0.0),
+            keyframe(self.color.as_str(), 0.15),
+            keyframe(background.as_str(), 1.0),
+        ]
+        .into_iter()
+        .collect();
+
+        let options = AnimateOptions::new(self.duration).easing(self.timing_fn.clone());
+
+        let anim = animate(el, Some(&keyframes.into()), &options);
+        anim.set_id(FLASH_ANIMATION_ID);
+        anim
+    }
+}
+
+/// The `id` of the animations of [`FlashAnimation::flash`], to find the running ones.
+const FLASH_ANIMATION_ID: &str = "leptos-animate:flash";
+
+impl Default for FlashAnimation {
+    fn default() -> Self {
+        Self::new("rgba(255, 220, 0, 0.4)", Duration::from_millis(600))
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FlashKeyframe<'a> {
+    background_color: &'a str,
+}
+
+// Like `ExpandCollapseAnimation`, this needs to read from the element.
+impl EnterAnimationHandler for FlashAnimation {
+    fn animate(&self, el: &web_sys::HtmlElement) -> web_sys::Animation {
+        self.flash(el)
+    }
+}
+
+/// An animation of `box-shadow`, for example to lift an element that gets picked up or
+/// highlighted and to drop it again afterwards.
+///
+/// It can be used as an enter animation, or run directly using [`BoxShadowAnimation::play`]. Like
+/// all animations in this crate it doesn't fill, so set the final shadow on the element itself
+/// (for example using a class) at the same time as starting the animation.
+///
+/// The browser can only interpolate between shadows with the same number of layers where each
+/// layer is either `inset` in both or in neither, otherwise the shadow jumps halfway through. In
+/// debug builds, a warning is logged for shadows that don't match.
+///
+/// # Example
+/// ```
+/// let lift = BoxShadowAnimation::new(
+///     "0 1px 2px rgba(0, 0, 0, 0.2)",
+///     "0 8px 24px rgba(0, 0, 0, 0.25)",
+///     Duration::from_millis(200),
+/// );
+///
+/// let on_drag_start = move |_| {
+///     dragging.set(true);
+///     if let Some(el) = card.get_untracked() {
+///         lift.play(&el);
+///     }
+/// };
+/// ```
+#[derive(Clone)]
+pub struct BoxShadowAnimation {
+    pub from: Oco<'static, str>,
+    pub to: Oco<'static, str>,
+    pub timing_fn: Oco<'static, str>,
+    pub duration: Duration,
+}
+
+impl BoxShadowAnimation {
+    pub fn new<F: Into<Oco<'static, str>>, T: Into<Oco<'static, str>>>(
+        from: F,
+        to: T,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            timing_fn: Oco::Borrowed("ease-out"),
+            duration,
+        }
+    }
+
+    pub fn timing_fn<TF: Into<Oco<'static, str>>>(mut self, timing_fn: TF) -> Self {
+        self.timing_fn = timing_fn.into();
+        self
+    }
+
+    /// The same animation in the opposite direction, for example to drop an element again.
+    pub fn reversed(&self) -> Self {
+        Self {
+            from: self.to.clone(),
+            to: self.from.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// Animate the shadow of the element from `from` to `to`.
+    pub fn play(&self, el: &web_sys::HtmlElement) -> web_sys::Animation {
+        let r = self.config();
+        let keyframes: Array = r.keyframes.iter().map(keyframe_to_js).collect();
+
+        animate(el, Some(&keyframes.into()), &r.timing.to_options())
+    }
+
+    fn config(&self) -> AnimationConfig<BoxShadowProps> {
+        #[cfg(debug_assertions)]
+        if let Err(err) = check_box_shadows_match(&self.from, &self.to) {
+            logging::warn!(
+                "box-shadow {:?} can't be animated to {:?}: {}",
+                self.from.as_str(),
+                self.to.as_str(),
+                err
+            );
+        }
+
+        AnimationConfig {
+            timing: Timing::new(self.duration).timing_fn(self.timing_fn.clone()),
+            keyframes: vec![
+                BoxShadowProps {
+                    box_shadow: self.from.to_string(),
+                },
+                BoxShadowProps {
+                    box_shadow: self.to.to_string(),
+                },
+            ],
+            pseudo_element: None,
+            from_current: false,
+        }
+    }
+}
+
+#[doc(hidden)]
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoxShadowProps {
+    box_shadow: String,
+}
+
+impl EnterAnimation for BoxShadowAnimation {
+    type Props = BoxShadowProps;
+
+    fn enter(&self, _el: &web_sys::Element) -> AnimationConfig<Self::Props> {
+        self.config()
+    }
+}
+
+/// Check that two `box-shadow` values can be interpolated, see [`BoxShadowAnimation`].
+#[cfg(debug_assertions)]
+fn check_box_shadows_match(from: &str, to: &str) -> Result<(), String> {
+    // Split into layers at the commas that aren't inside of a color function like `rgba()`.
+    fn layers(shadow: &str) -> Vec<&str> {
+        let shadow = shadow.trim();
+        if shadow == "none" {
+            return Vec::new();
+        }
+
+        let mut depth = 0;
+        let mut start = 0;
+        let mut layers = Vec::new();
+        for (i, c) in shadow.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    layers.push(shadow[start..i].trim());
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        layers.push(shadow[start..].trim());
+        layers
+    }
+
+    let is_inset = |layer: &&str| layer.split_whitespace().any(|part| part == "inset");
+
+    let (from, to) = (layers(from), layers(to));
+
+    // `none` gets expanded to transparent shadows of the other value.
+    if from.is_empty() || to.is_empty() {
+        return Ok(());
+    }
+
+    if from.len() != to.len() {
+        return Err(format!("they have {} and {} layers", from.len(), to.len()));
+    }
+
+    if let Some(i) = (0..from.len()).find(|&i| is_inset(&from[i]) != is_inset(&to[i])) {
+        return Err(format!("layer {} is only inset in one of them", i + 1));
+    }
+
+    Ok(())
+}
+
+/// Wrapper that limits the duration of another animation, for example so that tiny moves don't
+/// finish instantly and large moves don't take seconds, or to cut off an occasional overly long
+/// spring of a [`DynamicsAnimation`].
+///
+/// The timing function is kept, so a clamped animation runs the same curve faster or slower.
+///
+/// # Example
+/// ```
+/// let move_anim = ClampDuration::new(
+///     DynamicsAnimation::new(2.0, 0.65, 0.0),
+///     Duration::from_millis(150),
+///     Duration::from_millis(600),
+/// );
+/// ```
+pub struct ClampDuration<A> {
+    pub anim: A,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl<A> ClampDuration<A> {
+    /// Panics if `min > max`.
+    pub fn new(anim: A, min: Duration, max: Duration) -> Self {
+        assert!(
+            min <= max,
+            "ClampDuration: min ({min:?}) is greater than max ({max:?})"
+        );
+        Self { anim, min, max }
+    }
+}
+
+impl<A: EnterAnimation> EnterAnimation for ClampDuration<A> {
+    type Props = A::Props;
+
+    fn enter(&self, el: &web_sys::Element) -> AnimationConfig<Self::Props> {
+        let mut r = self.anim.enter(el);
+        r.timing = r.timing.clamp_duration(self.min, self.max);
+        r
+    }
+}
+
+impl<A: LeaveAnimation> LeaveAnimation for ClampDuration<A> {
+    type Props = A::Props;
+
+    fn leave(&self) -> AnimationConfig<Self::Props> {
+        let mut r = self.anim.leave();
+        r.timing = r.timing.clamp_duration(self.min, self.max);
+        r
+    }
+}
+
+impl<A: MoveAnimation> MoveAnimation for ClampDuration<A> {
+    fn animate(&self, from: ElementSnapshot, to: ElementSnapshot) -> AnimationConfigMove {
+        let mut r = self.anim.animate(from, to);
+        r.timing = r.timing.clamp_duration(self.min, self.max);
+        r.vertical_timing = r
+            .vertical_timing
+            .map(|timing| timing.clamp_duration(self.min, self.max));
+        r
+    }
+}
+
+impl<A: ResizeAnimation> ResizeAnimation for ClampDuration<A> {
+    fn animate(&self, from: Extent, to: Extent) -> AnimationConfigResize {
+        let mut r = self.anim.animate(from, to);
+        r.timing = r.timing.clamp_duration(self.min, self.max);
+        r
+    }
+}
+
+/// Wrapper that delays another animation, for example to let the entries of an
+/// [`AnimatedLayout`][crate::AnimatedLayout] enter one after another.
+///
+/// The delay is added to the one of the wrapped animation.
+///
+/// # Example
+/// ```
+/// LayoutEntry::new(WindowKind::EditOptions, options_view)
+///     .enter_anim(Delay::new(SlideAnimation::default(), Duration::from_millis(200)))
+/// ```
+pub struct Delay<A> {
+    pub anim: A,
+    pub delay: Duration,
+}
+
+impl<A> Delay<A> {
+    pub fn new(anim: A, delay: Duration) -> Self {
+        Self { anim, delay }
+    }
+}
+
+impl<A: EnterAnimation> EnterAnimation for Delay<A> {
+    type Props = A::Props;
+
+    fn enter(&self, el: &web_sys::Element) -> AnimationConfig<Self::Props> {
+        let mut r = self.anim.enter(el);
+        r.timing.delay += self.delay;
+        r
+    }
+}
+
+impl<A: LeaveAnimation> LeaveAnimation for Delay<A> {
+    type Props = A::Props;
+
+    fn leave(&self) -> AnimationConfig<Self::Props> {
+        let mut r = self.anim.leave();
+        r.timing.delay += self.delay;
+        r
+    }
+}
+
+impl<A: MoveAnimation> MoveAnimation for Delay<A> {
+    fn animate(&self, from: ElementSnapshot, to: ElementSnapshot) -> AnimationConfigMove {
+        let mut r = self.anim.animate(from, to);
+        r.timing.delay += self.delay;
+        if let Some(timing) = &mut r.vertical_timing {
+            timing.delay += self.delay;
+        }
+        r
+    }
+}
+
+impl<A: ResizeAnimation> ResizeAnimation for Delay<A> {
+    fn animate(&self, from: Extent, to: Extent) -> AnimationConfigResize {
+        let mut r = self.anim.animate(from, to);
+        r.timing.delay += self.delay;
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn assert_samples(actual: Vec<f64>, expected: &[f64], tolerance: f64, name: &str) {
+        assert_eq!(actual.len(), expected.len());
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!(
+                (actual - expected).abs() < tolerance,
+                "{name}: expected {expected:?}, got {actual:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn cubic_bezier_matches_css_keywords() {
+        // Reference values at 25%, 50% and 75%, computed by bisection.
+        let cases = [
+            (
+                "ease",
+                0.25,
+                0.1,
+                0.25,
+                1.0,
+                [0.408510591, 0.802403388, 0.960458978],
+            ),
+            (
+                "ease-in",
+                0.42,
+                0.0,
+                1.0,
+                1.0,
+                [0.093464651, 0.315356813, 0.621861869],
+            ),
+            (
+                "ease-out",
+                0.0,
+                0.0,
+                0.58,
+                1.0,
+                [0.378138131, 0.684643187, 0.906535349],
+            ),
+            (
+                "ease-in-out",
+                0.42,
+                0.0,
+                0.58,
+                1.0,
+                [0.129161931, 0.5, 0.870838069],
+            ),
+        ];
+
+        for (name, x1, y1, x2, y2, [a, b, c]) in cases {
+            let samples = sample_cubic_bezier(x1, y1, x2, y2, 5);
+            assert_samples(samples, &[0.0, a, b, c, 1.0], 1e-6, name);
+        }
+    }
+
+    #[test]
+    fn cubic_bezier_linear() {
+        assert_samples(
+            sample_cubic_bezier(0.0, 0.0, 1.0, 1.0, 5),
+            &[0.0, 0.25, 0.5, 0.75, 1.0],
+            1e-6,
+            "linear",
+        );
+    }
+
+    #[test]
+    fn cubic_bezier_overshoot() {
+        // Goes below 0 and above 1, like a "back" easing.
+        assert_samples(
+            sample_cubic_bezier(0.68, -0.6, 0.32, 1.6, 5),
+            &[0.0, -0.097707742, 0.5, 1.097707742, 1.0],
+            1e-6,
+            "back",
+        );
+    }
+
+    #[test]
+    fn cubic_bezier_steep() {
+        // The curve is vertical at 50%, so the output is very sensitive to the solved time there.
+        assert_samples(
+            sample_cubic_bezier(1.0, 0.0, 0.0, 1.0, 5),
+            &[0.0, 0.029724606, 0.5, 0.970275394, 1.0],
+            1e-3,
+            "steep",
+        );
+    }
+
+    #[test]
+    fn cubic_bezier_count() {
+        assert_eq!(sample_cubic_bezier(0.25, 0.1, 0.25, 1.0, 0), [0.0, 1.0]);
+        assert_eq!(sample_cubic_bezier(0.25, 0.1, 0.25, 1.0, 20).len(), 20);
+    }
+
+    #[test]
+    fn linear_easing_css() {
+        let easing = LinearEasing::new()
+            .point(0.0)
+            .point_at(0.5, 25.0)
+            .point(1.0);
+        assert_eq!(easing.to_css(), "linear(0, 0.5 25%, 1)");
+
+        let easing = [0.0, 0.25, 1.0].into_iter().collect::<LinearEasing>();
+        assert_eq!(easing.to_css(), "linear(0, 0.25, 1)");
+    }
+
+    #[test]
+    fn linear_easing_with_too_few_points() {
+        assert_eq!(LinearEasing::new().to_css(), "linear");
+        assert_eq!(LinearEasing::new().point(0.5).to_css(), "linear");
+
+        let timing_fn: Oco<'static, str> = LinearEasing::new().into();
+        assert_eq!(timing_fn.as_str(), "linear");
+    }
+}
@@ -0,0 +1,80 @@
+use std::future::Future;
+
+use web_sys::js_sys::{self, Array};
+use web_sys::Animation;
+
+/// A single step of an [`AnimationSequence`].
+type Step = Box<dyn FnOnce() -> Vec<Animation>>;
+
+/// Runs animations in stages, for example an intro that first animates a list in, then a panel,
+/// then a button. Each step starts its animations and returns them, and the next step only runs
+/// once all of them have finished (or got cancelled), just like chaining
+/// [`wait_for`][crate::wait_for].
+///
+/// Steps run lazily, so they can look up elements that only got rendered by the steps before.
+/// A step that returns no animations continues with the next step right away.
+///
+/// # Example
+/// ```
+/// let options = AnimateOptions::new(Duration::from_millis(300)).easing("ease-out");
+///
+/// AnimationSequence::new()
+///     .step(move || {
+///         items
+///             .iter()
+///             .map(|item| animate(item, Some(&fade_in), &options))
+///             .collect::<Vec<_>>()
+///     })
+///     .step(move || [animate(&panel, Some(&slide_in), &options)])
+///     .step(move || [animate(&button, Some(&fade_in), &options)])
+///     .play();
+/// ```
+#[derive(Default)]
+pub struct AnimationSequence {
+    steps: Vec<Step>,
+}
+
+impl AnimationSequence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a step that starts animations. It can return a single animation wrapped in an array
+    /// or an `Option`, or a `Vec` of animations.
+    pub fn step<F, A>(mut self, step: F) -> Self
+    where
+        F: FnOnce() -> A + 'static,
+        A: IntoIterator<Item = Animation>,
+    {
+        self.steps
+            .push(Box::new(move || step().into_iter().collect()));
+        self
+    }
+
+    /// Returns a future that runs the steps one after another and resolves once the animations
+    /// of the last step have finished.
+    pub fn run(self) -> impl Future<Output = ()> {
+        async move {
+            for step in self.steps {
+                let finished: Array = step()
+                    .iter()
+                    .filter_map(|anim| anim.finished().ok())
+                    .collect();
+
+                if finished.length() == 0 {
+                    continue;
+                }
+
+                // Cancelled animations reject their `finished` promise, so wait for all of them
+                // to settle.
+                _ = wasm_bindgen_futures::JsFuture::from(js_sys::Promise::all_settled(&finished))
+                    .await;
+            }
+        }
+    }
+
+    /// Run the sequence in the background, see [`Self::run`].
+    pub fn play(self) {
+        leptos::spawn_local(self.run());
+    }
+}
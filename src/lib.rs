@@ -7,20 +7,40 @@
 //! This crate currently uses the Web Animations API, which means your animations need to be configured in code and not in CSS.
 //!
 //! Ensure using the `ssr` feature when building the ssr code, as web animations cannot be run on the server.
+//!
+//! Enable the `router` feature for [`AnimatedOutlet`], which animates route transitions of `leptos_router`.
 
+pub use animate::*;
+pub use animated_dialog::*;
 pub use animated_for::*;
 pub use animated_layout::*;
+pub use animated_number::*;
+#[cfg(feature = "router")]
+pub use animated_outlet::*;
 pub use animated_show::*;
 pub use animated_swap::*;
 pub use animation_defs::*;
+pub use animation_sequence::*;
 pub use position::*;
+pub use resize_animation::*;
+pub use shared_element::*;
 pub use size_transition::*;
+pub use validation_message::*;
 
+mod animate;
+mod animated_dialog;
 mod animated_for;
 mod animated_layout;
+mod animated_number;
+#[cfg(feature = "router")]
+mod animated_outlet;
 mod animated_show;
 mod animated_swap;
 mod animation_defs;
+mod animation_sequence;
 pub mod dynamics;
 mod position;
+mod resize_animation;
+mod shared_element;
 mod size_transition;
+mod validation_message;
@@ -9,6 +9,31 @@ fn fuzzy_compare(a: f64, b: f64) -> bool {
     (a - b).abs() < 0.1
 }
 
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+impl Position {
+    /// The length of the vector from the origin to this position.
+    pub fn magnitude(&self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    /// The straight-line distance between two positions.
+    pub fn distance(&self, other: &Position) -> f64 {
+        (*other - *self).magnitude()
+    }
+
+    /// Linear interpolation between `a` (at `t = 0`) and `b` (at `t = 1`). To ease the
+    /// interpolation, pass `t` through an easing function first.
+    pub fn lerp(a: Position, b: Position, t: f64) -> Position {
+        Position {
+            x: lerp(a.x, b.x, t),
+            y: lerp(a.y, b.y, t),
+        }
+    }
+}
+
 impl PartialEq for Position {
     fn eq(&self, other: &Self) -> bool {
         fuzzy_compare(self.x, other.x) && fuzzy_compare(self.y, other.y)
@@ -56,6 +81,26 @@ pub struct Extent {
     pub height: f64,
 }
 
+impl Extent {
+    pub fn area(&self) -> f64 {
+        self.width * self.height
+    }
+
+    /// Width divided by height. Returns `None` for elements without height.
+    pub fn aspect_ratio(&self) -> Option<f64> {
+        (self.height != 0.0).then(|| self.width / self.height)
+    }
+
+    /// Linear interpolation between `a` (at `t = 0`) and `b` (at `t = 1`). See
+    /// [`Position::lerp`].
+    pub fn lerp(a: Extent, b: Extent, t: f64) -> Extent {
+        Extent {
+            width: lerp(a.width, b.width, t),
+            height: lerp(a.height, b.height, t),
+        }
+    }
+}
+
 impl From<(f64, f64)> for Extent {
     fn from((width, height): (f64, f64)) -> Self {
         Self { width, height }
@@ -67,3 +112,49 @@ impl PartialEq for Extent {
         fuzzy_compare(self.width, other.width) && fuzzy_compare(self.height, other.height)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn magnitude_and_distance() {
+        assert_eq!(Position { x: 3.0, y: 4.0 }.magnitude(), 5.0);
+        assert_eq!(Position::default().magnitude(), 0.0);
+
+        let a = Position { x: 1.0, y: 1.0 };
+        let b = Position { x: -2.0, y: 5.0 };
+        assert_eq!(a.distance(&b), 5.0);
+        assert_eq!(b.distance(&a), 5.0);
+        assert_eq!(a.distance(&a), 0.0);
+    }
+
+    #[test]
+    fn area_and_aspect_ratio() {
+        let extent = Extent::from((16.0, 9.0));
+        assert_eq!(extent.area(), 144.0);
+        assert_eq!(extent.aspect_ratio(), Some(16.0 / 9.0));
+
+        let collapsed = Extent::from((10.0, 0.0));
+        assert_eq!(collapsed.area(), 0.0);
+        assert_eq!(collapsed.aspect_ratio(), None);
+    }
+
+    #[test]
+    fn lerp() {
+        let a = Position { x: 0.0, y: 10.0 };
+        let b = Position { x: 100.0, y: -10.0 };
+        assert_eq!(Position::lerp(a, b, 0.0), a);
+        assert_eq!(Position::lerp(a, b, 1.0), b);
+        assert_eq!(Position::lerp(a, b, 0.25), Position { x: 25.0, y: 5.0 });
+
+        // Overshooting easings go beyond the endpoints.
+        assert_eq!(Position::lerp(a, b, 1.5), Position { x: 150.0, y: -20.0 });
+
+        let a = Extent::from((10.0, 20.0));
+        let b = Extent::from((30.0, 20.0));
+        assert_eq!(Extent::lerp(a, b, 0.0), a);
+        assert_eq!(Extent::lerp(a, b, 1.0), b);
+        assert_eq!(Extent::lerp(a, b, 0.5), Extent::from((20.0, 20.0)));
+    }
+}
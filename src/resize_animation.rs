@@ -0,0 +1,130 @@
+use std::rc::Rc;
+
+use web_sys::js_sys::Array;
+use web_sys::Animation;
+
+use crate::animate::keyframe_to_js;
+use crate::{animate, Extent, ResizeAnimation, SizeTransitionTechnique, SlidingAnimation};
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResizeKeyframe {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    margin_right: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    margin_bottom: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    width: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    height: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    box_sizing: Option<&'static str>,
+}
+
+impl ResizeKeyframe {
+    /// The keyframe for the element being at `extent` while its actual size is `to`.
+    fn new(extent: Extent, to: Extent, technique: SizeTransitionTechnique) -> Self {
+        match technique {
+            SizeTransitionTechnique::Margin => Self {
+                margin_right: Some(format!("{}px", extent.width - to.width)),
+                margin_bottom: Some(format!("{}px", extent.height - to.height)),
+                width: None,
+                height: None,
+                box_sizing: None,
+            },
+            SizeTransitionTechnique::Size => Self {
+                margin_right: None,
+                margin_bottom: None,
+                width: Some(format!("{}px", extent.width)),
+                height: Some(format!("{}px", extent.height)),
+                box_sizing: Some("border-box"),
+            },
+        }
+    }
+}
+
+/// Wrapper trait for [`ResizeAnimation`] to be used as a dyn trait. Shared by
+/// [`AnyResizeAnimation`] and [`SizeTransition`][crate::SizeTransition].
+trait ResizeAnimationHandler {
+    fn animate(
+        &self,
+        el: &web_sys::HtmlElement,
+        from: Extent,
+        to: Extent,
+        technique: SizeTransitionTechnique,
+    ) -> Animation;
+}
+
+impl<T: ResizeAnimation> ResizeAnimationHandler for T {
+    fn animate(
+        &self,
+        el: &web_sys::HtmlElement,
+        from: Extent,
+        to: Extent,
+        technique: SizeTransitionTechnique,
+    ) -> Animation {
+        let r = self.animate(from, to);
+
+        let keyframes: Array = [from, to]
+            .into_iter()
+            .map(|extent| keyframe_to_js(&ResizeKeyframe::new(extent, to, technique)))
+            .collect();
+
+        animate(el, Some(&keyframes.into()), &r.timing.to_options())
+    }
+}
+
+/// Any struct that implements [`ResizeAnimation`] can be converted into this using `into()`.
+///
+/// This animates the `width` and `height` of any element directly, so it can be used for
+/// containers such as the one of [`AnimatedLayout`][crate::AnimatedLayout] or for custom
+/// components. [`AnySizeTransitionAnimation`][crate::AnySizeTransitionAnimation] wraps it to
+/// choose the [`SizeTransitionTechnique`] of a [`SizeTransition`][crate::SizeTransition].
+#[derive(Clone)]
+pub struct AnyResizeAnimation {
+    anim: Rc<dyn ResizeAnimationHandler>,
+}
+
+impl AnyResizeAnimation {
+    /// Animate the border box of the element from one size to another. Since the animation
+    /// doesn't fill, the element should already have its final size.
+    ///
+    /// # Example
+    /// ```
+    /// let from = Extent { width: rect.width(), height: rect.height() };
+    /// // ... change the contents of the element ...
+    /// let rect = el.get_bounding_client_rect();
+    /// let to = Extent { width: rect.width(), height: rect.height() };
+    ///
+    /// AnyResizeAnimation::from(SlidingAnimation::default()).animate(&el, from, to);
+    /// ```
+    pub fn animate(&self, el: &web_sys::HtmlElement, from: Extent, to: Extent) -> Animation {
+        self.anim
+            .animate(el, from, to, SizeTransitionTechnique::Size)
+    }
+
+    /// Like [`Self::animate`], but using the given technique, see [`SizeTransitionTechnique`].
+    pub(crate) fn animate_using(
+        &self,
+        el: &web_sys::HtmlElement,
+        from: Extent,
+        to: Extent,
+        technique: SizeTransitionTechnique,
+    ) -> Animation {
+        self.anim.animate(el, from, to, technique)
+    }
+}
+
+impl<T: ResizeAnimation + 'static> From<T> for AnyResizeAnimation {
+    fn from(anim: T) -> Self {
+        Self {
+            anim: Rc::new(anim),
+        }
+    }
+}
+
+impl Default for AnyResizeAnimation {
+    fn default() -> Self {
+        SlidingAnimation::default().into()
+    }
+}
@@ -0,0 +1,87 @@
+use leptos::window;
+use web_sys::Animation;
+
+use crate::animated_for::MoveAnimSettings;
+use crate::{AnyMoveAnimation, ElementSnapshot, Extent, Position};
+
+/// FLIP-animates a single element from where it was to where it is now, for example when it moves
+/// from a list into a "selected" area in a different container. This is the single-element
+/// version of the move animations of [`AnimatedFor`][crate::AnimatedFor].
+///
+/// Take the snapshot before the element is moved (or replaced), then call [`Self::play`] once the
+/// DOM has been updated. Positions are measured relative to the document, so the old and the new
+/// parent don't need to have anything in common. If Leptos re-creates the element in its new
+/// place, pass the new element to `play`; it will start where the old one was.
+///
+/// # Example
+/// ```
+/// let transition = SharedElementTransition::snapshot(&card);
+/// selected.set(Some(id));
+///
+/// request_animation_frame(move || {
+///     if let Some(card) = selected_card.get_untracked() {
+///         transition.play(&card, SlidingAnimation::default());
+///     }
+/// });
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct SharedElementTransition {
+    from: ElementSnapshot,
+    scale_size: bool,
+}
+
+impl SharedElementTransition {
+    /// Record where the element currently is on the page, including the effects of any
+    /// animations that are still running on it. To interrupt a previous transition of the same
+    /// element smoothly, cancel its animation only after taking the new snapshot.
+    pub fn snapshot(el: &web_sys::HtmlElement) -> Self {
+        Self {
+            from: document_snapshot(el),
+            scale_size: false,
+        }
+    }
+
+    /// Whether to also animate a size change, by scaling the element using a transform. The
+    /// children of the element get the inverse scale to stay undistorted, see `scale_size` on
+    /// [`AnimatedFor`][crate::AnimatedFor] for the constraints.
+    pub fn scale_size(mut self, scale_size: bool) -> Self {
+        self.scale_size = scale_size;
+        self
+    }
+
+    /// Animate the element from the snapshot to its current position.
+    pub fn play(
+        self,
+        el: &web_sys::HtmlElement,
+        move_anim: impl Into<AnyMoveAnimation>,
+    ) -> Animation {
+        let to = document_snapshot(el);
+
+        let settings = MoveAnimSettings {
+            animate_size: self.scale_size,
+            device_pixel_ratio: Some(window().device_pixel_ratio()),
+            use_translate3d: true,
+            scale_size: self.scale_size,
+            clip_on_shrink: false,
+        };
+
+        move_anim.into().animate(el, self.from, to, settings)
+    }
+}
+
+/// The position of the element relative to the document, and its size.
+fn document_snapshot(el: &web_sys::HtmlElement) -> ElementSnapshot {
+    let rect = el.get_bounding_client_rect();
+    let window = window();
+
+    ElementSnapshot {
+        position: Position {
+            x: rect.left() + window.scroll_x().unwrap_or_default(),
+            y: rect.top() + window.scroll_y().unwrap_or_default(),
+        },
+        extent: Extent {
+            width: rect.width(),
+            height: rect.height(),
+        },
+    }
+}
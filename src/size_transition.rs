@@ -1,17 +1,29 @@
-use std::rc::Rc;
-
-use crate::{animate, Extent, ResizeAnimation, SlidingAnimation};
+use crate::{AnyResizeAnimation, Extent, ResizeAnimation, SlidingAnimation};
 use leptos::html::AnyElement;
 use leptos::*;
 use leptos_use::use_resize_observer;
-use web_sys::js_sys::Array;
-use web_sys::{FillMode, ResizeObserverSize};
-
-#[derive(serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-struct SizeTransitionKeyframe {
-    margin_right: String,
-    margin_bottom: String,
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::ResizeObserverSize;
+
+/// How [`SizeTransition`] animates the size of its wrapper.
+///
+/// The `ResizeObserver` that detects the size changes also sees the size changes caused by the
+/// animation itself. If those were animated directly, every frame of the animation would be
+/// observed as a new size change and start a new animation, which is why the default technique
+/// animates the margins instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SizeTransitionTechnique {
+    /// Animate a negative `margin-right` / `margin-bottom`, which changes the space the wrapper
+    /// takes up in the layout without changing its observed size. This breaks if the wrapper's
+    /// margins are used by the surrounding layout.
+    #[default]
+    Margin,
+
+    /// Animate the `width` and `height` of the wrapper directly, ignoring the observed size
+    /// changes while the animation is running. Size changes of the contents during the animation
+    /// are only picked up once it has finished, animating from there to the new size.
+    Size,
 }
 
 /// Animates the size of its contents whenever that changes.
@@ -21,13 +33,30 @@ struct SizeTransitionKeyframe {
 ///
 /// Uses a ResizeObserver to listen for size changes. Wraps the children in a span with `display:inline-block` and `position:relative`.
 ///
-/// **Note:** The size is animated using `margin-right` (for width) and margin-bottom (for height) instead of `width`/`height` in order to not trip up the underlying `ResizeObserver`.
+/// **Note:** By default, the size is animated using `margin-right` (for width) and margin-bottom (for height) instead of `width`/`height` in order to not trip up the underlying `ResizeObserver`. See [`SizeTransitionTechnique`] for the alternative.
+///
+/// # Aspect ratio
+/// Sizes that come from `aspect-ratio` are animated like any other size change: the
+/// `ResizeObserver` reports the size that results from the new ratio, and the space the wrapper
+/// takes up is animated from the old size to that one. The contents themselves switch to their
+/// new ratio immediately, so they can stick out of the wrapper while it is still growing or
+/// leave a gap while it shrinks; use `overflow: hidden` on a parent if that is a problem.
+///
+/// The other dimension must not depend on the wrapper, for example a contained video with a fixed
+/// `width` and an `aspect-ratio` works, but one with `width: 100%` does not (see above). With
+/// [`SizeTransitionTechnique::Size`], the wrapper gets an explicit `width` and `height` during the
+/// animation, so contents that take their size from the wrapper would be resized along with it.
 #[component]
 pub fn SizeTransition(
     children: Children,
     #[prop(into, default=SlidingAnimation::default().into())]
     resize_anim: AnySizeTransitionAnimation,
+    /// How the size is animated, see [`SizeTransitionTechnique`].
+    #[prop(optional)]
+    technique: SizeTransitionTechnique,
 ) -> impl IntoView {
+    let resize_anim = resize_anim.technique(technique);
+
     view! {
         <span style="display:inline-block; position:relative;" use:animated_size=resize_anim>
             {children()}
@@ -35,46 +64,33 @@ pub fn SizeTransition(
     }
 }
 
-trait SizeTransitionHandler {
-    fn animate(&self, el: HtmlElement<AnyElement>, snapshot: Extent, new_snapshot: Extent);
-}
-
-impl<T: ResizeAnimation> SizeTransitionHandler for T {
-    fn animate(&self, el: HtmlElement<AnyElement>, snapshot: Extent, new_snapshot: Extent) {
-        let r = self.animate(snapshot, new_snapshot);
-
-        let arr: Array = [snapshot, new_snapshot]
-            .into_iter()
-            .map(|snapshot| {
-                serde_wasm_bindgen::to_value(&SizeTransitionKeyframe {
-                    margin_right: format!("{}px", snapshot.width - new_snapshot.width),
-                    margin_bottom: format!("{}px", snapshot.height - new_snapshot.height),
-                })
-                .unwrap()
-            })
-            .collect();
-
-        animate(
-            &el,
-            Some(&arr.into()),
-            &(r.duration.as_secs_f64() * 1000.0).into(),
-            FillMode::None,
-            r.timing_fn.as_ref().map(|v| v.as_str()),
-        );
-    }
-}
-
 /// Any struct that implements [`ResizeAnimation`] can be converted into this using into(). This
 /// conversion is typically done automatically.
 #[derive(Clone)]
 pub struct AnySizeTransitionAnimation {
-    anim: Rc<dyn SizeTransitionHandler>,
+    anim: AnyResizeAnimation,
+    technique: SizeTransitionTechnique,
 }
 
-impl<T: SizeTransitionHandler + 'static> From<T> for AnySizeTransitionAnimation {
+impl AnySizeTransitionAnimation {
+    /// Set how the size is animated, see [`SizeTransitionTechnique`].
+    pub fn technique(mut self, technique: SizeTransitionTechnique) -> Self {
+        self.technique = technique;
+        self
+    }
+}
+
+impl<T: ResizeAnimation + 'static> From<T> for AnySizeTransitionAnimation {
     fn from(anim: T) -> Self {
+        AnyResizeAnimation::from(anim).into()
+    }
+}
+
+impl From<AnyResizeAnimation> for AnySizeTransitionAnimation {
+    fn from(anim: AnyResizeAnimation) -> Self {
         Self {
-            anim: Rc::new(anim),
+            anim,
+            technique: SizeTransitionTechnique::default(),
         }
     }
 }
@@ -99,7 +115,15 @@ impl From<()> for AnySizeTransitionAnimation {
 pub fn animated_size(el: HtmlElement<AnyElement>, size_anim: AnySizeTransitionAnimation) {
     let snapshot = StoredValue::new(None::<Extent>);
 
+    // Whether a `SizeTransitionTechnique::Size` animation is running, whose size changes must
+    // not be mistaken for changes of the contents.
+    let animating = StoredValue::new(false);
+
     use_resize_observer((&*el).clone(), move |entries, _| {
+        if animating.get_value() {
+            return;
+        }
+
         let rects = entries[0].border_box_size();
         let rect: ResizeObserverSize = rects.get(0).into();
         let new_snapshot = Extent {
@@ -108,7 +132,28 @@ pub fn animated_size(el: HtmlElement<AnyElement>, size_anim: AnySizeTransitionAn
         };
 
         if let Some(snapshot) = snapshot.get_value() {
-            size_anim.anim.animate(el.clone(), snapshot, new_snapshot);
+            // Nothing to animate, and a `SizeTransitionTechnique::Size` animation would only pin
+            // the wrapper to its current size.
+            if snapshot == new_snapshot {
+                return;
+            }
+
+            let anim =
+                size_anim
+                    .anim
+                    .animate_using(&el, snapshot, new_snapshot, size_anim.technique);
+
+            if size_anim.technique == SizeTransitionTechnique::Size {
+                animating.set_value(true);
+
+                let closure = Closure::<dyn Fn(web_sys::Event)>::new(move |_| {
+                    animating.try_set_value(false);
+                })
+                .into_js_value();
+
+                anim.set_onfinish(Some(closure.unchecked_ref()));
+                anim.set_oncancel(Some(closure.unchecked_ref()));
+            }
         }
 
         snapshot.set_value(Some(new_snapshot));
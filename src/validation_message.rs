@@ -0,0 +1,69 @@
+use leptos::*;
+
+use crate::{
+    AnimatedShow, AnyEnterAnimation, AnyLeaveAnimation, AnySizeTransitionAnimation,
+    ExpandCollapseAnimation, ExpandEnterAnimation, SizeTransition, SlidingAnimation,
+};
+
+/// A message below a form field, such as a validation error, that expands into view when it
+/// appears and collapses when it goes away, pushing the following fields along. When the text
+/// changes while the message is shown, its height changes smoothly as well.
+///
+/// This combines an [`AnimatedShow`] with `keep_in_flow` and a [`SizeTransition`] for the text.
+/// The last message stays visible while the message collapses, so it doesn't go blank first.
+///
+/// # Example
+/// ```
+/// let error = Signal::derive(move || {
+///     let name = name.get();
+///     if name.is_empty() {
+///         Some("Please enter a name".to_string())
+///     } else if name.len() < 3 {
+///         Some("The name is too short".to_string())
+///     } else {
+///         None
+///     }
+/// });
+///
+/// view! {
+///     <input prop:value=name on:input=move |ev| name.set(event_target_value(&ev)) />
+///     <ValidationMessage message=error class="field-error" />
+/// }
+/// ```
+#[component]
+pub fn ValidationMessage(
+    /// The message to show, or `None` to hide it.
+    #[prop(into)]
+    message: Signal<Option<String>>,
+
+    /// Class of the `div` that contains the message.
+    #[prop(optional, into)]
+    class: Option<Oco<'static, str>>,
+
+    /// See this prop on [`AnimatedShow`].
+    #[prop(default = ExpandEnterAnimation::default().into(), into)]
+    enter_anim: AnyEnterAnimation,
+
+    /// See this prop on [`AnimatedShow`].
+    #[prop(default = ExpandCollapseAnimation::default().into(), into)]
+    leave_anim: AnyLeaveAnimation,
+
+    /// Animation for the height when the text changes while the message is shown.
+    #[prop(default = SlidingAnimation::default().into(), into)]
+    resize_anim: AnySizeTransitionAnimation,
+) -> impl IntoView {
+    let when = Signal::derive(move || message.with(Option::is_some));
+
+    // Keeps the last message while the leave-animation is running.
+    let text = create_memo(move |prev: Option<&String>| {
+        message.get().or_else(|| prev.cloned()).unwrap_or_default()
+    });
+
+    view! {
+        <AnimatedShow when enter_anim leave_anim keep_in_flow=true>
+            <div class=class.clone() role="alert">
+                <SizeTransition resize_anim=resize_anim.clone()>{text}</SizeTransition>
+            </div>
+        </AnimatedShow>
+    }
+}
@@ -0,0 +1,480 @@
+//! Browser tests for `AnimatedFor`. Run them using `wasm-pack test --headless --firefox`.
+
+mod common;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use common::*;
+use leptos::*;
+use leptos_animate::*;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// An item of a column, with its key as `data-key`.
+fn item(k: &u32) -> impl IntoView {
+    view! { <div class="item" data-key=*k style="width: 100px; height: 20px">{*k}</div> }
+}
+
+fn opacity(el: &web_sys::HtmlElement) -> f64 {
+    computed_style(el, "opacity").parse().unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn leave_continues_from_interrupted_enter() {
+    let items = RwSignal::new(vec![1]);
+    let fade = || FadeAnimation::new(Duration::from_millis(1000), "linear");
+
+    let container = mount(move || {
+        view! {
+            <AnimatedFor
+                each=move || items.get()
+                key=|k: &u32| *k
+                children=item
+                enter_anim=fade()
+                leave_anim=fade()
+            />
+        }
+    });
+    next_frame().await;
+
+    items.set(vec![1, 2]);
+    next_frame().await;
+
+    // Halfway through the enter.
+    let el = by_key(&container, 2);
+    for anim in animations(&el) {
+        anim.set_current_time(Some(500.0));
+    }
+    let interrupted_opacity = opacity(&el);
+
+    items.set(vec![1]);
+    next_frame().await;
+    next_frame().await;
+
+    // The very start of the leave.
+    for anim in animations(&el) {
+        anim.pause().unwrap();
+        anim.set_current_time(Some(0.0));
+    }
+
+    assert!((opacity(&el) - interrupted_opacity).abs() < 0.05);
+}
+
+#[wasm_bindgen_test]
+async fn leaving_table_rows_keep_their_position() {
+    let rows = RwSignal::new(vec![1, 2, 3, 4, 5]);
+
+    let container = mount(move || {
+        view! {
+            <table>
+                <tbody>
+                    <AnimatedFor
+                        each=move || rows.get()
+                        key=|k: &u32| *k
+                        children=|k: &u32| view! { <tr data-key=*k><td>{*k}</td></tr> }
+                        table_rows=true
+                    />
+                </tbody>
+            </table>
+        }
+    });
+    next_frame().await;
+
+    rows.set(vec![1, 2, 4, 5]);
+    next_frame().await;
+
+    assert_eq!(rendered_keys(&container), ["1", "2", "3", "4", "5"]);
+
+    // Rows that leave one after another stay in place as well.
+    rows.set(vec![1, 4, 5]);
+    next_frame().await;
+
+    assert_eq!(rendered_keys(&container), ["1", "2", "3", "4", "5"]);
+}
+
+#[wasm_bindgen_test]
+async fn rapid_filter_changes_leave_no_ghosts() {
+    const WORDS: [&str; 6] = [
+        "apple",
+        "apricot",
+        "avocado",
+        "banana",
+        "blueberry",
+        "cherry",
+    ];
+    let filter = RwSignal::new(String::new());
+
+    let container = mount(move || {
+        view! {
+            <AnimatedFor
+                each=move || {
+                    filter.with(|filter| {
+                        WORDS.into_iter().filter(|w| w.starts_with(filter.as_str())).collect::<Vec<_>>()
+                    })
+                }
+                key=|w: &&str| *w
+                children=|w: &&str| view! { <div class="item" data-key=*w>{*w}</div> }
+            />
+        }
+    });
+    next_frame().await;
+
+    // Some changes come in faster than a frame, others while the previous ones are animating.
+    for (typed, wait) in [
+        ("a", false),
+        ("ap", false),
+        ("apr", true),
+        ("ap", true),
+        ("a", false),
+    ] {
+        filter.set(typed.to_string());
+        if wait {
+            next_frame().await;
+        }
+    }
+    filter.set("b".to_string());
+    filter.set("bl".to_string());
+    next_frame().await;
+
+    // Jump to the end of the transitions, including the leave-animations that only start now.
+    next_frame().await;
+    finish_animations(&container).await;
+
+    assert_eq!(rendered_keys(&container), ["blueberry"]);
+}
+
+#[wasm_bindgen_test]
+async fn shrinking_item_is_clipped_until_the_move_finished() {
+    let items = RwSignal::new(vec![1]);
+    let wide = RwSignal::new(true);
+    let shrink = StoredValue::new(false);
+
+    let container = mount(move || {
+        view! {
+            <AnimatedFor
+                each=move || items.get()
+                key=|k: &u32| *k
+                children=move |k: &u32| {
+                    view! {
+                        <div
+                            data-key=*k
+                            style="height: 20px; white-space: nowrap"
+                            style:width=move || if wide.get() { "300px" } else { "100px" }
+                        >
+                            "Some text that is wider than the narrow item"
+                        </div>
+                    }
+                }
+                animate_size=true
+                clip_on_shrink=true
+                on_after_snapshot=Callback::new(move |_| {
+                    if shrink.get_value() {
+                        wide.set(false);
+                    }
+                })
+            />
+        }
+    });
+    next_frame().await;
+
+    // The width changes between the snapshots of the transition.
+    shrink.set_value(true);
+    items.set(vec![1]);
+    next_frame().await;
+
+    let el = by_key(&container, 1);
+    for anim in animations(&el) {
+        anim.pause().unwrap();
+        anim.set_current_time(Some(0.0));
+    }
+    assert_px(el.get_bounding_client_rect().width(), 300.0);
+    assert_eq!(computed_style(&el, "overflow"), "hidden");
+
+    finish_animations(&container).await;
+
+    assert_px(el.get_bounding_client_rect().width(), 100.0);
+    assert_eq!(computed_style(&el, "overflow"), "visible");
+    assert_eq!(el.style().get_property_value("overflow").unwrap(), "");
+}
+
+#[wasm_bindgen_test]
+async fn custom_elements_move() {
+    // A block-level custom element that renders its contents through its shadow DOM.
+    web_sys::js_sys::eval(
+        "customElements.get('test-card') || customElements.define('test-card', class extends HTMLElement {
+            constructor() {
+                super();
+                this.attachShadow({ mode: 'open' }).innerHTML =
+                    '<style>:host { display: block; height: 20px }</style><slot></slot>';
+            }
+        })",
+    )
+    .unwrap();
+
+    let items = RwSignal::new(vec![1, 2, 3]);
+
+    let container = mount(move || {
+        view! {
+            <AnimatedFor
+                each=move || items.get()
+                key=|k: &u32| *k
+                children=|k: &u32| view! { <test-card data-key=*k>{*k}</test-card> }
+            />
+        }
+    });
+    next_frame().await;
+
+    let (_, top) = screen_position(&by_key(&container, 1));
+
+    items.set(vec![3, 1, 2]);
+    next_frame().await;
+
+    // The very start of the move.
+    let el = by_key(&container, 3);
+    let anims = animations(&el);
+    assert!(!anims.is_empty());
+    for anim in anims {
+        anim.pause().unwrap();
+        anim.set_current_time(Some(0.0));
+    }
+    assert_px(screen_position(&el).1, top + 40.0);
+
+    finish_animations(&container).await;
+
+    assert_px(screen_position(&el).1, top);
+    assert_eq!(rendered_keys(&container), ["3", "1", "2"]);
+}
+
+#[wasm_bindgen_test]
+async fn moves_land_in_a_scaled_container() {
+    let items = RwSignal::new(vec![1, 2, 3]);
+
+    let container = mount(move || {
+        view! {
+            <div style="position: relative; transform: scale(2); transform-origin: 0 0">
+                <AnimatedFor
+                    each=move || items.get()
+                    key=|k: &u32| *k
+                    children=item
+                    animate_size=true
+                    position_source=PositionSource::BoundingRect
+                />
+            </div>
+        }
+    });
+    next_frame().await;
+
+    let (_, top) = screen_position(&by_key(&container, 1));
+
+    items.set(vec![3, 1, 2]);
+    next_frame().await;
+
+    // The very start of the move, where the item is still drawn at its old place, which is
+    // 2 * 40px further down on screen.
+    let el = by_key(&container, 3);
+    for anim in animations(&el) {
+        anim.pause().unwrap();
+        anim.set_current_time(Some(0.0));
+    }
+    assert_px(screen_position(&el).1, top + 80.0);
+    assert_px(el.get_bounding_client_rect().width(), 200.0);
+
+    finish_animations(&container).await;
+
+    assert_px(screen_position(&el).1, top);
+    assert_px(el.get_bounding_client_rect().width(), 200.0);
+}
+
+#[wasm_bindgen_test]
+async fn unmounting_mid_transition_stops_everything() {
+    let mounted = RwSignal::new(true);
+    let items = RwSignal::new(vec![1, 2, 3]);
+    let fade = || FadeAnimation::new(Duration::from_millis(300), "linear");
+
+    // Counts the callbacks that fire after the component is gone.
+    let fired = Rc::new(Cell::new(0));
+    let count = {
+        let fired = fired.clone();
+        move || {
+            let fired = fired.clone();
+            Callback::new(move |_| fired.set(fired.get() + 1))
+        }
+    };
+    let (on_enter_end, on_leave_end, on_transition_end) = (count(), count(), count());
+
+    let container = mount(move || {
+        view! {
+            <Show when=move || mounted.get()>
+                <AnimatedFor
+                    each=move || items.get()
+                    key=|k: &u32| *k
+                    children=item
+                    enter_anim=fade()
+                    leave_anim=fade()
+                    on_enter_end
+                    on_leave_end
+                    on_transition_end
+                />
+            </Show>
+        }
+    });
+    next_frame().await;
+
+    items.set(vec![2, 3, 4]);
+    next_frame().await;
+
+    let anims = animations(&container);
+    assert!(!anims.is_empty());
+
+    mounted.set(false);
+    next_frame().await;
+
+    assert!(rendered_keys(&container).is_empty());
+    for anim in &anims {
+        assert_eq!(anim.play_state(), web_sys::AnimationPlayState::Idle);
+    }
+
+    // Past the end of the animations and the safety-net timeouts of the leaves.
+    sleep(Duration::from_millis(1500)).await;
+
+    assert_eq!(fired.get(), 0);
+    assert!(rendered_keys(&container).is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn no_inline_styles_remain_after_animations() {
+    let items = RwSignal::new(vec![1, 2, 3]);
+
+    let container = mount(move || {
+        view! {
+            <AnimatedFor
+                each=move || items.get()
+                key=|k: &u32| *k
+                children=item
+                use_will_change=true
+                transition_contain="layout"
+            />
+        }
+    });
+    next_frame().await;
+
+    items.set(vec![4, 3, 1]);
+    next_frame().await;
+
+    // Interrupts the moves and the enter with new ones.
+    items.set(vec![1, 4, 3]);
+    next_frame().await;
+
+    finish_animations(&container).await;
+    next_frame().await;
+
+    let inline_styles = |el: &web_sys::HtmlElement| {
+        let style = el.style();
+        (0..style.length())
+            .map(|i| style.item(i))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(rendered_keys(&container), ["1", "4", "3"]);
+    for el in by_class(&container, "item") {
+        assert_eq!(inline_styles(&el), ["width", "height"]);
+    }
+    assert!(inline_styles(&container).is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn will_change_restores_inline_value() {
+    let items = RwSignal::new(vec![1, 2, 3]);
+
+    let container = mount(move || {
+        view! {
+            <AnimatedFor
+                each=move || items.get()
+                key=|k: &u32| *k
+                children=|k: &u32| {
+                    view! {
+                        <div data-key=*k class="item" style="height: 20px; will-change: contents">
+                            {*k}
+                        </div>
+                    }
+                }
+                use_will_change=true
+            />
+        }
+    });
+    next_frame().await;
+
+    items.set(vec![3, 2, 1]);
+    next_frame().await;
+
+    assert_eq!(
+        by_key(&container, 1)
+            .style()
+            .get_property_value("will-change")
+            .unwrap(),
+        "transform, opacity"
+    );
+
+    // Interrupts the moves with new ones.
+    items.set(vec![1, 2, 3]);
+    next_frame().await;
+
+    finish_animations(&container).await;
+    next_frame().await;
+
+    for el in by_class(&container, "item") {
+        assert_eq!(
+            el.style().get_property_value("will-change").unwrap(),
+            "contents"
+        );
+    }
+}
+
+#[wasm_bindgen_test]
+async fn batched_start_keeps_inline_styles() {
+    let items = RwSignal::new(Vec::<u32>::new());
+
+    let container = mount(move || {
+        view! {
+            <AnimatedFor
+                each=move || items.get()
+                key=|k: &u32| *k
+                children=|k: &u32| {
+                    view! {
+                        <div data-key=*k class="item" style="height: 20px; visibility: visible">
+                            {*k}
+                        </div>
+                    }
+                }
+                batch_size=1
+            />
+        }
+    });
+    next_frame().await;
+
+    items.set(vec![1, 2, 3]);
+    next_frame().await;
+
+    // The last item waits for its batch.
+    assert_eq!(
+        by_key(&container, 3)
+            .style()
+            .get_property_value("visibility")
+            .unwrap(),
+        "hidden"
+    );
+
+    next_frame().await;
+    next_frame().await;
+    finish_animations(&container).await;
+
+    for el in by_class(&container, "item") {
+        assert_eq!(
+            el.style().get_property_value("visibility").unwrap(),
+            "visible"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Browser tests for `AnimatedOutlet`. Run them using
+//! `wasm-pack test --headless --firefox -- --features router`.
+
+#![cfg(feature = "router")]
+
+mod common;
+
+use std::time::Duration;
+
+use common::*;
+use leptos::*;
+use leptos_animate::*;
+use leptos_router::*;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// A page that is tall enough to scroll the window, with its name as `data-key`.
+fn page(name: &'static str) -> impl Fn() -> View {
+    move || view! { <div data-key=name style="height: 3000px">{name}</div> }.into_view()
+}
+
+#[wasm_bindgen_test]
+async fn unmounting_during_scroll_compensation() {
+    let mounted = RwSignal::new(true);
+
+    let container = mount(move || {
+        view! {
+            <Router>
+                <A href="/a" class="to-a">"A"</A>
+                <A href="/b" class="to-b">"B"</A>
+                <Routes>
+                    <Route
+                        path=""
+                        view=move || {
+                            view! {
+                                <Show when=move || mounted.get()>
+                                    <div style="position: relative">
+                                        <AnimatedOutlet />
+                                    </div>
+                                </Show>
+                            }
+                        }
+                    >
+                        <Route path="a" view=page("a") />
+                        <Route path="b" view=page("b") />
+                    </Route>
+                </Routes>
+            </Router>
+        }
+    });
+
+    by_class(&container, "to-a")[0].click();
+    next_frame().await;
+    next_frame().await;
+    let leaving = by_key(&container, "a");
+
+    window().scroll_to_with_x_and_y(0.0, 500.0);
+    next_frame().await;
+
+    // Starts the leave of page `a`, which compensates for the window scrolling from now on.
+    by_class(&container, "to-b")[0].click();
+    next_frame().await;
+
+    mounted.set(false);
+    next_frame().await;
+    assert!(rendered_keys(&container).is_empty());
+
+    // A scroll listener that outlived the outlet would still move the leaving page.
+    let top = leaving.style().get_property_value("top").unwrap();
+    window().scroll_to_with_x_and_y(0.0, 200.0);
+    next_frame().await;
+    next_frame().await;
+    assert_eq!(leaving.style().get_property_value("top").unwrap(), top);
+
+    // Past the timeout that would have removed the listener.
+    sleep(Duration::from_millis(600)).await;
+
+    window().scroll_to_with_x_and_y(0.0, 0.0);
+}
@@ -0,0 +1,43 @@
+//! Browser tests for `AnimatedSwap`. Run them using `wasm-pack test --headless --firefox`.
+
+mod common;
+
+use common::*;
+use leptos::*;
+use leptos_animate::*;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn swap_after_content_changed_in_place() {
+    let tab = RwSignal::new("a");
+    let version = RwSignal::new(0);
+
+    let content = Signal::derive(move || {
+        let key = format!("{}-{}", tab.get(), version.get());
+        view! { <div data-key=key.clone() style="height: 20px">{key}</div> }.into_view()
+    });
+
+    let container = mount(move || {
+        view! { <AnimatedSwap content swap_key=Signal::derive(move || tab.get().to_string()) /> }
+    });
+    next_frame().await;
+
+    // Replaces the element without a swap.
+    version.set(1);
+    next_frame().await;
+    assert_eq!(rendered_keys(&container), ["a-1"]);
+
+    tab.set("b");
+    next_frame().await;
+
+    // The element that is on screen leaves, not the one that got replaced before.
+    let leaving = by_key(&container, "a-1");
+    assert!(!animations(&leaving).is_empty());
+    assert_eq!(computed_style(&leaving, "position"), "absolute");
+
+    finish_animations(&container).await;
+
+    assert_eq!(rendered_keys(&container), ["b-1"]);
+}
@@ -0,0 +1,118 @@
+//! Helpers for the browser tests. Run them using `wasm-pack test --headless --firefox`.
+
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use leptos::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::js_sys;
+
+/// Mounts the view into a new container at the end of the body and returns the container.
+pub fn mount<F, N>(f: F) -> web_sys::HtmlElement
+where
+    F: FnOnce() -> N + 'static,
+    N: IntoView,
+{
+    let container: web_sys::HtmlElement =
+        document().create_element("div").unwrap().unchecked_into();
+    document().body().unwrap().append_child(&container).unwrap();
+
+    mount_to(container.clone(), f);
+    container
+}
+
+/// Resolves after `duration`.
+pub async fn sleep(duration: Duration) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        window()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                &resolve,
+                duration.as_millis() as i32,
+            )
+            .unwrap();
+    });
+    JsFuture::from(promise).await.unwrap();
+}
+
+/// Resolves in the next animation frame. The animations of a change are started in a microtask,
+/// so they are running by then.
+pub async fn next_frame() {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        window().request_animation_frame(&resolve).unwrap();
+    });
+    JsFuture::from(promise).await.unwrap();
+}
+
+/// All elements with the class inside the container, in document order.
+pub fn by_class(container: &web_sys::HtmlElement, class: &str) -> Vec<web_sys::HtmlElement> {
+    let elements = container.get_elements_by_class_name(class);
+    (0..elements.length())
+        .filter_map(|i| elements.item(i))
+        .map(JsCast::unchecked_into)
+        .collect()
+}
+
+/// The element with the `data-key` inside the container.
+pub fn by_key(container: &web_sys::HtmlElement, key: impl ToString) -> web_sys::HtmlElement {
+    container
+        .query_selector(&format!("[data-key='{}']", key.to_string()))
+        .unwrap()
+        .unwrap()
+        .unchecked_into()
+}
+
+/// The `data-key`s of the elements inside the container, in document order.
+pub fn rendered_keys(container: &web_sys::HtmlElement) -> Vec<String> {
+    let elements = container.get_elements_by_tag_name("*");
+    (0..elements.length())
+        .filter_map(|i| elements.item(i))
+        .filter_map(|el| el.get_attribute("data-key"))
+        .collect()
+}
+
+/// The position of the element in the viewport, including the transforms of its animations.
+pub fn screen_position(el: &web_sys::Element) -> (f64, f64) {
+    let rect = el.get_bounding_client_rect();
+    (rect.left(), rect.top())
+}
+
+/// The computed value of the CSS property, including the effects of running animations.
+pub fn computed_style(el: &web_sys::Element, property: &str) -> String {
+    window()
+        .get_computed_style(el)
+        .unwrap()
+        .unwrap()
+        .get_property_value(property)
+        .unwrap()
+}
+
+/// All animations in the container, including the ones of its descendants.
+pub fn animations(container: &web_sys::HtmlElement) -> Vec<web_sys::Animation> {
+    let mut options = web_sys::GetAnimationsOptions::new();
+    options.subtree(true);
+
+    container
+        .get_animations_with_options(&options)
+        .iter()
+        .map(JsCast::unchecked_into)
+        .collect()
+}
+
+/// Jump to the end of all animations in the container and wait for their `onfinish` handlers.
+pub async fn finish_animations(container: &web_sys::HtmlElement) {
+    for anim in animations(container) {
+        _ = anim.finish();
+    }
+    next_frame().await;
+}
+
+/// Asserts that two lengths in px are equal, up to rounding to device pixels.
+#[track_caller]
+pub fn assert_px(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 0.5,
+        "expected {expected}px, got {actual}px"
+    );
+}